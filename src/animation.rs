@@ -0,0 +1,208 @@
+use crate::render::{self, Render};
+use std::fs::{self, File};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+// Recording the steps of a simulation. Solvers call frame() (or frame_with(),
+// when drawing the state takes some work) after each step; nothing happens
+// unless --animate <file> was given. A .cast file can be played back with
+// asciinema, a .ppm file name becomes a numbered image per frame
+// (day.ppm -> day-0001.ppm, day-0002.ppm, ...).
+
+// Seconds between frames of a .cast recording
+const FRAME_TIME: f64 = 0.1;
+
+// The .cast header is padded to this many bytes so it can be rewritten in place
+const HEADER_LEN: usize = 80;
+
+enum Output {
+    // The header holds the largest frame size so far, and is rewritten
+    // whenever a frame is wider or taller than any before it
+    Cast(File, (usize, usize)),
+    Ppm(PathBuf),
+}
+
+pub struct Recorder {
+    output: Output,
+    skip: usize,
+    max: Option<usize>,
+    seen: usize,
+    frames: usize,
+}
+
+impl Recorder {
+    // Keep one frame out of every skip, and stop after max frames
+    pub fn new<P: AsRef<Path>>(path: P, skip: usize, max: Option<usize>) -> io::Result<Recorder> {
+        let path = path.as_ref();
+        let output = match path.extension().and_then(|e| e.to_str()) {
+            Some("cast") => Output::Cast(File::create(path)?, (0, 0)),
+            Some("ppm") => Output::Ppm(path.to_path_buf()),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Expected a .cast or .ppm file: {}", path.display()),
+                ))
+            }
+        };
+        Ok(Recorder {
+            output,
+            skip: skip.max(1),
+            max,
+            seen: 0,
+            frames: 0,
+        })
+    }
+
+    // Frames written so far
+    #[allow(dead_code)]
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    // Only draws the frame if it will be kept
+    pub fn record<R, F>(&mut self, f: F) -> io::Result<()>
+    where
+        R: Render,
+        F: FnOnce() -> R,
+    {
+        let keep =
+            self.seen.is_multiple_of(self.skip) && self.max.is_none_or(|max| self.frames < max);
+        self.seen += 1;
+        if !keep {
+            return Ok(());
+        }
+
+        let r = f();
+        match &mut self.output {
+            Output::Cast(file, size) => {
+                let (width, height) = r.size();
+                if self.frames == 0 || width > size.0 || height > size.1 {
+                    *size = (width.max(size.0), height.max(size.1));
+                    let header = format!(
+                        "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+                        size.0, size.1
+                    );
+                    file.seek(SeekFrom::Start(0))?;
+                    writeln!(file, "{:<1$}", header, HEADER_LEN - 1)?;
+                    file.seek(SeekFrom::End(0))?;
+                }
+                // clear the screen, then draw the frame
+                let screen =
+                    format!("\x1b[H\x1b[2J{}", render::ascii(&r, true)).replace('\n', "\r\n");
+                writeln!(
+                    file,
+                    "[{:.3}, \"o\", {}]",
+                    self.frames as f64 * FRAME_TIME,
+                    serde_json::to_string(&screen).unwrap()
+                )?;
+            }
+            Output::Ppm(path) => {
+                fs::write(numbered(path, self.frames + 1), render::ppm(&r, 4))?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+// day.ppm -> day-0001.ppm
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+    path.with_file_name(format!("{}-{:04}.ppm", stem, n))
+}
+
+static RECORDER: OnceLock<Mutex<Recorder>> = OnceLock::new();
+
+// Set once from the command line
+pub fn start(path: &str, skip: usize, max: Option<usize>) {
+    let recorder =
+        Recorder::new(path, skip, max).unwrap_or_else(|e| panic!("Unable to record: {}", e));
+    if RECORDER.set(Mutex::new(recorder)).is_err() {
+        panic!("Already recording");
+    }
+}
+
+pub fn frame(r: &dyn Render) {
+    frame_with(|| r);
+}
+
+pub fn frame_with<R, F>(f: F)
+where
+    R: Render,
+    F: FnOnce() -> R,
+{
+    if let Some(recorder) = RECORDER.get() {
+        recorder
+            .lock()
+            .unwrap()
+            .record(f)
+            .unwrap_or_else(|e| panic!("Unable to write frame: {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Text;
+    use std::env;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent-animation-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn test_cast() {
+        let path = scratch("test.cast");
+        let mut recorder = Recorder::new(&path, 2, None).unwrap();
+        for n in 0..5 {
+            recorder
+                .record(|| Text::new(&format!("{}.\n.#", n)))
+                .unwrap();
+        }
+        assert_eq!(recorder.frames(), 3);
+        // a larger frame later on grows the header, unless it is skipped
+        recorder.record(|| Text::new("......")).unwrap();
+        recorder.record(|| Text::new("...\n.\n.")).unwrap();
+        assert_eq!(recorder.frames(), 4);
+        drop(recorder);
+
+        let cast = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].len(), HEADER_LEN - 1);
+        assert_eq!(
+            lines[0].trim_end(),
+            "{\"version\": 2, \"width\": 3, \"height\": 3}"
+        );
+        assert!(lines[1].starts_with("[0.000, \"o\", \"\\u001b[H\\u001b[2J"));
+        assert!(lines[3].starts_with("[0.200, \"o\", "));
+        // frames 0, 2 and 4 were kept
+        assert!(lines[3].contains("m4\\u001b"));
+        assert!(lines[3].ends_with("\\r\\n\"]"));
+    }
+
+    #[test]
+    fn test_ppm() {
+        let path = scratch("frames.ppm");
+        let mut recorder = Recorder::new(&path, 1, Some(2)).unwrap();
+        let mut drawn = 0;
+        for _ in 0..4 {
+            recorder
+                .record(|| {
+                    drawn += 1;
+                    Text::new("#.")
+                })
+                .unwrap();
+        }
+        assert_eq!((recorder.frames(), drawn), (2, 2));
+        assert!(numbered(&path, 1).exists());
+        assert!(numbered(&path, 2).exists());
+        assert!(!numbered(&path, 3).exists());
+        assert!(numbered(&path, 2).ends_with("frames-0002.ppm"));
+
+        assert!(Recorder::new(scratch("frames.gif"), 1, None).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+extern crate scoped_threadpool;
+use crate::grid::Grid;
+use scoped_threadpool::Pool;
+
+// A cellular automaton on a grid: every step, each cell is replaced by
+// rule(cell, neighbors), where the neighborhood decides which cells are
+// neighbors (adjacent, first visible in each direction, hex tiles, ...).
+// The next generation is written into a second buffer, which is swapped in
+// at the end of the step, so nothing is cloned as the automaton runs.
+
+// Neighbors of (y, x) in axial hex coordinates stored on a grid
+#[allow(dead_code)]
+pub const HEX_OFFSETS: [(i32, i32); 6] = [(-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0)];
+
+pub trait Neighborhood<T>: Sync {
+    // Push the cells around pt that the rule should see
+    fn neighbors<'a>(&self, grid: &'a Grid<T>, pt: (usize, usize), out: &mut Vec<&'a T>);
+}
+
+// Cells one offset away
+pub struct Adjacent(pub &'static [(i32, i32)]);
+
+impl<T> Neighborhood<T> for Adjacent {
+    fn neighbors<'a>(&self, grid: &'a Grid<T>, pt: (usize, usize), out: &mut Vec<&'a T>) {
+        for delta in self.0 {
+            if let Some(p) = grid.offset(pt, *delta) {
+                out.push(&grid[p]);
+            }
+        }
+    }
+}
+
+// The first cell in each direction that can't be seen through
+pub struct Visible<F>(pub &'static [(i32, i32)], pub F);
+
+impl<T, F> Neighborhood<T> for Visible<F>
+where
+    F: Fn(&T) -> bool + Sync,
+{
+    fn neighbors<'a>(&self, grid: &'a Grid<T>, pt: (usize, usize), out: &mut Vec<&'a T>) {
+        for delta in self.0 {
+            if let Some((_, cell)) = grid.ray(pt, *delta).find(|(_, cell)| !(self.1)(cell)) {
+                out.push(cell);
+            }
+        }
+    }
+}
+
+pub struct Automaton<T, N, R> {
+    current: Grid<T>,
+    next: Grid<T>,
+    neighborhood: N,
+    rule: R,
+    steps: usize,
+    pool: Option<Pool>,
+}
+
+impl<T, N, R> Automaton<T, N, R>
+where
+    T: Clone + PartialEq + Send + Sync,
+    N: Neighborhood<T>,
+    R: Fn(&T, &[&T]) -> T + Sync,
+{
+    pub fn new(grid: Grid<T>, neighborhood: N, rule: R) -> Automaton<T, N, R> {
+        Automaton {
+            next: grid.clone(),
+            current: grid,
+            neighborhood,
+            rule,
+            steps: 0,
+            pool: None,
+        }
+    }
+
+    // Update rows on several threads (1 turns this off again)
+    pub fn parallel(mut self, threads: usize) -> Automaton<T, N, R> {
+        self.pool = if threads > 1 {
+            Some(Pool::new(threads as u32))
+        } else {
+            None
+        };
+        self
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.current
+    }
+
+    // Steps taken so far
+    #[allow(dead_code)]
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    // Change every cell in place, outside of the rule (not counted as a step)
+    pub fn map<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T),
+    {
+        self.current.values_mut().for_each(f);
+    }
+
+    // Apply the rule to every cell. Returns true if anything changed.
+    pub fn step(&mut self) -> bool {
+        let Automaton {
+            current,
+            next,
+            neighborhood,
+            rule,
+            pool,
+            ..
+        } = self;
+        let current = &*current;
+        let update = |y: usize, row: &mut [T]| {
+            let mut around = Vec::with_capacity(8);
+            for (x, cell) in row.iter_mut().enumerate() {
+                around.clear();
+                neighborhood.neighbors(current, (y, x), &mut around);
+                *cell = rule(&current[(y, x)], &around);
+            }
+        };
+
+        match pool {
+            Some(pool) => pool.scoped(|scope| {
+                let update = &update;
+                for (y, row) in next.rows_mut().enumerate() {
+                    scope.execute(move || update(y, row));
+                }
+            }),
+            None => {
+                for (y, row) in next.rows_mut().enumerate() {
+                    update(y, row);
+                }
+            }
+        }
+
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.steps += 1;
+        self.current != self.next
+    }
+
+    #[allow(dead_code)]
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    // Step until nothing changes. Returns the number of steps that changed something.
+    pub fn run_until_stable(&mut self) -> usize {
+        self.run_until_stable_with(|_| {})
+    }
+
+    // The same, handing the grid to on_step after each step that changed something
+    pub fn run_until_stable_with<F>(&mut self, mut on_step: F) -> usize
+    where
+        F: FnMut(&Grid<T>),
+    {
+        let mut changed = 0;
+        while self.step() {
+            on_step(self.grid());
+            changed += 1;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::OFFSETS_8;
+    use crate::puzzle_input;
+
+    fn life(cell: &bool, around: &[&bool]) -> bool {
+        let alive = around.iter().filter(|c| ***c).count();
+        alive == 3 || (*cell && alive == 2)
+    }
+
+    fn parse(input: &str) -> Grid<bool> {
+        Grid::parse(&puzzle_input::split_string(input), |c| c == '#')
+    }
+
+    #[test]
+    fn test_life() {
+        let blinker = parse(
+            ".....
+            ..#..
+            ..#..
+            ..#..
+            .....",
+        );
+        let mut blinking = Automaton::new(blinker.clone(), Adjacent(&OFFSETS_8), life);
+        assert!(blinking.step());
+        assert_eq!(
+            blinking.grid().render(|c| if *c { '#' } else { '.' }),
+            ".....\n.....\n.###.\n.....\n.....\n"
+        );
+        assert!(blinking.step());
+        assert_eq!(blinking.grid(), &blinker);
+        assert_eq!(blinking.steps(), 2);
+
+        let block = parse(
+            "....
+            .##.
+            .##.
+            ....",
+        );
+        let mut still = Automaton::new(block.clone(), Adjacent(&OFFSETS_8), life);
+        assert_eq!(still.run_until_stable(), 0);
+        assert_eq!(still.steps(), 1);
+        assert_eq!(still.into_grid(), block);
+
+        // a lone cell dies, then nothing changes
+        let mut lonely = Automaton::new(parse("...\n.#."), Adjacent(&OFFSETS_8), life);
+        let mut seen = Vec::new();
+        assert_eq!(lonely.run_until_stable_with(|g| seen.push(g.clone())), 1);
+        assert_eq!(seen, [parse("...\n...")]);
+    }
+
+    #[test]
+    fn test_parallel() {
+        let glider = parse(
+            ".#......
+            ..#.....
+            ###.....
+            ........
+            ........
+            ........",
+        );
+        let mut serial = Automaton::new(glider.clone(), Adjacent(&OFFSETS_8), life);
+        let mut parallel = Automaton::new(glider, Adjacent(&OFFSETS_8), life).parallel(4);
+        serial.run(8);
+        parallel.run(8);
+        assert_eq!(serial.grid(), parallel.grid());
+        // a glider moves one cell diagonally every 4 steps
+        assert!(serial.grid()[(4, 3)] && serial.grid()[(3, 4)]);
+    }
+
+    #[test]
+    fn test_neighborhoods() {
+        let grid = parse(
+            "#.#
+            ...
+            #..",
+        );
+        let count = |n: &dyn Neighborhood<bool>, pt| {
+            let mut out = Vec::new();
+            n.neighbors(&grid, pt, &mut out);
+            (out.len(), out.iter().filter(|c| ***c).count())
+        };
+        assert_eq!(count(&Adjacent(&OFFSETS_8), (1, 1)), (8, 3));
+        assert_eq!(count(&Adjacent(&HEX_OFFSETS), (1, 1)), (6, 2));
+        assert_eq!(count(&Adjacent(&HEX_OFFSETS), (0, 0)), (2, 0));
+        // looking past empty cells from a corner
+        let visible = Visible(&OFFSETS_8, |c: &bool| !*c);
+        assert_eq!(count(&visible, (0, 0)), (2, 2));
+        assert_eq!(count(&visible, (2, 2)), (3, 3));
+    }
+}
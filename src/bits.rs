@@ -0,0 +1,215 @@
+use std::fmt;
+
+// Reading and writing values as sequences of bits, most significant bit first.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EndOfData {
+    pub position: usize,
+    pub requested: usize,
+    pub available: usize,
+}
+
+impl fmt::Display for EndOfData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "End of data at bit {}: wanted {} bits, {} left",
+            self.position, self.requested, self.available
+        )
+    }
+}
+
+// Bytes from a string of hex digits (whitespace is ignored).
+// An odd number of digits is padded with a trailing 0.
+pub fn hex_bytes(hex: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or_else(|| format!("Not a hex digit: {:?}", c))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).unwrap_or(&0))
+        .collect())
+}
+
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    len: usize,
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader::with_len(data, data.len() * 8)
+    }
+
+    // Only the first len bits of data are readable
+    pub fn with_len(data: &'a [u8], len: usize) -> BitReader<'a> {
+        assert!(
+            len <= data.len() * 8,
+            "{} bits from {} bytes",
+            len,
+            data.len()
+        );
+        BitReader {
+            data,
+            len,
+            position: 0,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, EndOfData> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    // The next n (up to 64) bits as an unsigned value
+    pub fn read_bits(&mut self, n: usize) -> Result<u64, EndOfData> {
+        assert!(n <= 64, "Can't read {} bits into a u64", n);
+        if n > self.remaining() {
+            return Err(EndOfData {
+                position: self.position,
+                requested: n,
+                available: self.remaining(),
+            });
+        }
+        let mut value = 0;
+        let mut left = n;
+        while left > 0 {
+            let offset = self.position % 8;
+            let take = (8 - offset).min(left);
+            let byte = self.data[self.position / 8] as u64;
+            let bits = (byte >> (8 - offset - take)) & ((1 << take) - 1);
+            value = value << take | bits;
+            self.position += take;
+            left -= take;
+        }
+        Ok(value)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitWriter {
+    data: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> BitWriter {
+        BitWriter::default()
+    }
+
+    // Number of bits written
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Written bits, with the last byte padded with zeros
+    #[allow(dead_code)]
+    pub fn bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn reader(&self) -> BitReader<'_> {
+        BitReader::with_len(&self.data, self.len)
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        self.write_bits(bit as u64, 1);
+    }
+
+    // The low n (up to 64) bits of value
+    pub fn write_bits(&mut self, value: u64, n: usize) {
+        assert!(n <= 64, "Can't write {} bits from a u64", n);
+        let mut left = n;
+        while left > 0 {
+            let offset = self.len % 8;
+            if offset == 0 {
+                self.data.push(0);
+            }
+            let take = (8 - offset).min(left);
+            let bits = (value >> (left - take)) & ((1 << take) - 1);
+            *self.data.last_mut().unwrap() |= (bits << (8 - offset - take)) as u8;
+            self.len += take;
+            left -= take;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let data = hex_bytes("D2FE28\n").unwrap();
+        assert_eq!(data, vec![0xd2, 0xfe, 0x28]);
+
+        let mut bits = BitReader::new(&data);
+        assert_eq!(bits.read_bits(3), Ok(6));
+        assert_eq!(bits.read_bits(3), Ok(4));
+        assert_eq!(bits.read_bit(), Ok(true));
+        assert_eq!(bits.read_bits(4), Ok(7));
+        assert_eq!(bits.position(), 11);
+        assert_eq!(bits.read_bits(13), Ok(0b1111000101000));
+        assert!(bits.is_empty());
+        assert_eq!(
+            bits.read_bits(1),
+            Err(EndOfData {
+                position: 24,
+                requested: 1,
+                available: 0
+            })
+        );
+
+        assert!(hex_bytes("12G").is_err());
+        assert_eq!(hex_bytes("ABC").unwrap(), vec![0xab, 0xc0]);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut w = BitWriter::new();
+        w.write_bits(6, 3);
+        w.write_bits(4, 3);
+        w.write_bits(0b10111, 5);
+        w.write_bits(0b11110, 5);
+        w.write_bits(0b00101, 5);
+        assert_eq!(w.len(), 21);
+        assert_eq!(w.bytes(), &[0xd2, 0xfe, 0x28][..]);
+
+        let mut r = w.reader();
+        assert_eq!(r.read_bits(21), Ok(0b110100101111111000101));
+        assert!(r.read_bit().is_err());
+
+        let mut w = BitWriter::new();
+        w.write_bits(u64::MAX, 64);
+        w.write_bit(false);
+        let mut r = w.reader();
+        assert_eq!(r.read_bits(64), Ok(u64::MAX));
+        assert_eq!(r.read_bit(), Ok(false));
+        assert_eq!(r.remaining(), 0);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Cycle detection for iterated functions: state(0) = initial, state(n+1) = step(state(n)).
+// A cycle has a first repeating step (start) and a period; a fixpoint has period 1.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    // The earliest step with the same state as step n
+    #[allow(dead_code)]
+    pub fn index(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome<S> {
+    // The cycle, and the repeated state
+    Cycle(Cycle, S),
+    // The step function stopped after this many steps, with the last state
+    Halted(usize, S),
+}
+
+// Step until the key of a state has been seen before, or the step function returns None.
+// The key lets the caller ignore parts of the state that don't affect what happens next.
+pub fn run_until_repeat<S, K, FS, FK>(initial: S, mut step: FS, mut key: FK) -> Outcome<S>
+where
+    K: Hash + Eq,
+    FS: FnMut(&S) -> Option<S>,
+    FK: FnMut(&S) -> K,
+{
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut current = initial;
+    let mut i = 0;
+    loop {
+        if let Some(start) = seen.insert(key(&current), i) {
+            return Outcome::Cycle(
+                Cycle {
+                    start,
+                    period: i - start,
+                },
+                current,
+            );
+        }
+        match step(&current) {
+            Some(next) => current = next,
+            None => return Outcome::Halted(i, current),
+        }
+        i += 1;
+    }
+}
+
+// Hash-based: remembers every state, so each state is only stepped once.
+// Returns the cycle and the state at its start. Never returns if the states don't repeat.
+#[allow(dead_code)]
+pub fn find_cycle<S, F>(initial: S, mut step: F) -> (Cycle, S)
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    match run_until_repeat(initial, |s| Some(step(s)), |s| s.clone()) {
+        Outcome::Cycle(cycle, state) => (cycle, state),
+        Outcome::Halted(..) => unreachable!(),
+    }
+}
+
+// Brent's algorithm: constant memory, at the cost of stepping states more than once
+#[allow(dead_code)]
+pub fn brent<S, F>(initial: S, mut step: F) -> (Cycle, S)
+where
+    S: Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    // find the period: the hare runs ahead in doubling stretches
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // find the start: walk two states one period apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    (Cycle { start, period }, tortoise)
+}
+
+// The state after n steps, skipping ahead once a cycle is found
+#[allow(dead_code)]
+pub fn nth<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history: Vec<S> = Vec::new();
+    let mut current = initial;
+    for i in 0..n {
+        if let Some(start) = seen.get(&current) {
+            let cycle = Cycle {
+                start: *start,
+                period: i - start,
+            };
+            return history.swap_remove(cycle.index(n));
+        }
+        seen.insert(current.clone(), i);
+        let next = step(&current);
+        history.push(current);
+        current = next;
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(start: usize, period: usize) -> Cycle {
+        Cycle { start, period }
+    }
+
+    // 3, 9, 27, 81, 43, 29, 87, 61, 83, 49, 47, 41, 23, 69, 7, 21, 63, 89, 67, 1, 3, ...
+    fn triple(n: &u32) -> u32 {
+        n * 3 % 100
+    }
+
+    // 0, 1, 2, 5, 26, 167, 95, 101, 2, 5, ...
+    fn tail(n: &u32) -> u32 {
+        (n * n + 1) % 255
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(find_cycle(3, triple), (cycle(0, 20), 3));
+        assert_eq!(brent(3, triple), (cycle(0, 20), 3));
+
+        let (found, state) = find_cycle(0, tail);
+        assert_eq!((found, state), (cycle(2, 6), 2));
+        assert_eq!(brent(0, tail), (found, state));
+        assert_eq!(nth(0, tail, found.start), state);
+        assert_eq!(nth(0, tail, found.start + found.period), state);
+
+        // fixpoint
+        assert_eq!(find_cycle(10, |n| n / 2), (cycle(4, 1), 0));
+    }
+
+    #[test]
+    fn test_nth() {
+        let mut slow = 3;
+        for _ in 0..1234 {
+            slow = triple(&slow);
+        }
+        assert_eq!(nth(3, triple, 1234), slow);
+        assert_eq!(nth(3, triple, 1_000_000_000_000), 3);
+        assert_eq!(nth(3, triple, 1_000_000_000_019), 1);
+        assert_eq!(nth(3, triple, 5), 29);
+        assert_eq!(nth(3, triple, 0), 3);
+    }
+
+    #[test]
+    fn test_run_until_repeat() {
+        // (position, total): only the position decides what happens next
+        let jumps = [2, 1, -2, 5];
+        let step = |s: &(i32, i32)| jumps.get(s.0 as usize).map(|j| (s.0 + j, s.1 + 1));
+        assert_eq!(
+            run_until_repeat((0, 0), step, |s| s.0),
+            Outcome::Cycle(cycle(0, 2), (0, 2))
+        );
+        assert_eq!(
+            run_until_repeat((3, 0), step, |s| s.0),
+            Outcome::Halted(1, (8, 1))
+        );
+    }
+}
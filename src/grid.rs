@@ -0,0 +1,291 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// A dense, rectangular grid addressed by (y, x), stored row by row.
+
+pub const OFFSETS_4: [(i32, i32); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+pub const OFFSETS_8: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    // One row per (trimmed, non-empty) line, one cell per character
+    pub fn parse<F>(input: &[String], f: F) -> Grid<T>
+    where
+        F: Fn(char) -> T,
+    {
+        let rows: Vec<&str> = input
+            .iter()
+            .map(|row| row.trim())
+            .filter(|row| !row.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut data = Vec::with_capacity(width * rows.len());
+        for row in &rows {
+            assert_eq!(width, row.chars().count(), "Ragged row {:?}", row);
+            data.extend(row.chars().map(&f));
+        }
+        Grid {
+            data,
+            width,
+            height: rows.len(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pt: (usize, usize)) -> bool {
+        pt.0 < self.height && pt.1 < self.width
+    }
+
+    pub fn get(&self, pt: (usize, usize)) -> Option<&T> {
+        if self.contains(pt) {
+            Some(&self.data[pt.0 * self.width + pt.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pt: (usize, usize)) -> Option<&mut T> {
+        if self.contains(pt) {
+            Some(&mut self.data[pt.0 * self.width + pt.1])
+        } else {
+            None
+        }
+    }
+
+    // The point one step away in the given (dy, dx) direction, if it is on the grid
+    pub fn offset(&self, pt: (usize, usize), delta: (i32, i32)) -> Option<(usize, usize)> {
+        let y = pt.0 as i64 + delta.0 as i64;
+        let x = pt.1 as i64 + delta.1 as i64;
+        if y < 0 || x < 0 || y >= self.height as i64 || x >= self.width as i64 {
+            None
+        } else {
+            Some((y as usize, x as usize))
+        }
+    }
+
+    fn around<'a>(
+        &'a self,
+        pt: (usize, usize),
+        offsets: &'a [(i32, i32)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |o| self.offset(pt, *o))
+    }
+
+    // Up, left, right, down
+    pub fn neighbors(&self, pt: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.around(pt, &OFFSETS_4)
+    }
+
+    // Including diagonals
+    #[allow(dead_code)]
+    pub fn neighbors8(&self, pt: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.around(pt, &OFFSETS_8)
+    }
+
+    // All points, row by row
+    pub fn points(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| (i / width, i % width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.points().zip(self.data.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut()
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.data[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    // Each row as a mutable slice, top to bottom
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.data.chunks_mut(self.width.max(1))
+    }
+
+    #[allow(dead_code)]
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.data.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    // Values walking away from (but not including) pt until the edge of the grid
+    pub fn ray(&self, pt: (usize, usize), delta: (i32, i32)) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            current: pt,
+            delta,
+        }
+    }
+
+    pub fn left(&self, pt: (usize, usize)) -> Ray<'_, T> {
+        self.ray(pt, (0, -1))
+    }
+
+    pub fn right(&self, pt: (usize, usize)) -> Ray<'_, T> {
+        self.ray(pt, (0, 1))
+    }
+
+    pub fn up(&self, pt: (usize, usize)) -> Ray<'_, T> {
+        self.ray(pt, (-1, 0))
+    }
+
+    pub fn down(&self, pt: (usize, usize)) -> Ray<'_, T> {
+        self.ray(pt, (1, 0))
+    }
+
+    pub fn is_edge(&self, pt: (usize, usize)) -> bool {
+        pt.0 == 0 || pt.1 == 0 || pt.0 + 1 == self.height || pt.1 + 1 == self.width
+    }
+
+    #[allow(dead_code)]
+    pub fn render<F>(&self, f: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            out.extend(self.row(y).map(&f));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pt: (usize, usize)) -> &T {
+        self.get(pt)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pt))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pt: (usize, usize)) -> &mut T {
+        self.get_mut(pt)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pt))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            for v in self.row(y) {
+                write!(f, "{}", v)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    current: (usize, usize),
+    delta: (i32, i32),
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = ((usize, usize), &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.grid.offset(self.current, self.delta)?;
+        self.current = next;
+        Some((next, &self.grid[next]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle_input;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse(
+            &puzzle_input::split_string(
+                "123
+                456",
+            ),
+            |c| c.to_digit(10).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(
+            grid.render(|d| if d % 2 == 0 { '#' } else { '.' }),
+            ".#.\n#.#\n"
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = digits();
+        let n: Vec<(usize, usize)> = grid.neighbors((0, 0)).collect();
+        assert_eq!(n, vec![(0, 1), (1, 0)]);
+        let n: Vec<(usize, usize)> = grid.neighbors((1, 1)).collect();
+        assert_eq!(n, vec![(0, 1), (1, 0), (1, 2)]);
+        assert_eq!(grid.neighbors8((0, 1)).count(), 5);
+        assert_eq!(grid.neighbors8((1, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_rows_columns_rays() {
+        let mut grid = digits();
+        assert_eq!(grid.row(1).copied().collect::<Vec<u32>>(), vec![4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<u32>>(), vec![3, 6]);
+        assert_eq!(
+            grid.left((1, 2)).map(|(_, v)| *v).collect::<Vec<u32>>(),
+            vec![5, 4]
+        );
+        assert_eq!(
+            grid.up((1, 2)).map(|(p, _)| p).collect::<Vec<_>>(),
+            vec![(0, 2)]
+        );
+        assert_eq!(grid.right((1, 2)).count(), 0);
+        assert_eq!(grid.down((0, 0)).count(), 1);
+
+        grid[(0, 0)] = 9;
+        assert_eq!(grid.points().next(), Some((0, 0)));
+        assert_eq!(grid.iter().last(), Some(((1, 2), &6)));
+        assert_eq!(grid.values().sum::<u32>(), 29);
+
+        let empty: Grid<u32> = Grid::parse(&[], |c| c.to_digit(10).unwrap());
+        assert_eq!(empty.column(0).count(), 0);
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+// Diagnostic output, kept apart from the answers: answers are printed to stdout,
+// everything else goes through these macros to stderr, if its level is enabled.
+//
+//   debug!("move {} from {} to {}", n, from, to);
+//
+// -q shows only errors, warnings are shown by default, and -v, -vv, -vvv turn on
+// info, debug and trace. --log puzzles_2019::compute=trace sets the level for
+// one module (and everything inside it).
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn from_u8(v: u8) -> Level {
+        match v {
+            1 => Level::Error,
+            2 => Level::Warn,
+            3 => Level::Info,
+            4 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+
+    // Default (0), -v (1), -vv (2), ...
+    pub fn from_verbosity(v: usize) -> Level {
+        Level::from_u8(Level::Warn as u8 + v.min(3) as u8)
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Level, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("Unknown log level: {:?}", s)),
+        }
+    }
+}
+
+// module=level, where module is a path inside the crate (puzzles_2019::compute)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    module: String,
+    level: Level,
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Filter, String> {
+        match s.split_once('=') {
+            Some((module, level)) => Ok(Filter {
+                module: module.trim_start_matches("crate::").to_string(),
+                level: level.parse()?,
+            }),
+            None => Err(format!("Expected module=level: {:?}", s)),
+        }
+    }
+}
+
+impl Filter {
+    fn matches(&self, module: &str) -> bool {
+        // module_path!() starts with the crate name
+        let module = module.split_once("::").map_or("", |(_, rest)| rest);
+        module == self.module
+            || (module.starts_with(&self.module) && module[self.module.len()..].starts_with("::"))
+    }
+}
+
+// The most specific filter wins
+fn level_for(filters: &[Filter], module: &str, default: Level) -> Level {
+    filters
+        .iter()
+        .filter(|f| f.matches(module))
+        .max_by_key(|f| f.module.len())
+        .map_or(default, |f| f.level)
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+static FILTERS: OnceLock<Vec<Filter>> = OnceLock::new();
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+// Set once from the command line
+pub fn set_filters(filters: Vec<Filter>) {
+    FILTERS
+        .set(filters)
+        .unwrap_or_else(|_| panic!("Log filters are already set"));
+}
+
+pub fn enabled(level: Level, module: &str) -> bool {
+    let default = Level::from_u8(LEVEL.load(Ordering::Relaxed));
+    match FILTERS.get() {
+        Some(filters) => level <= level_for(filters, module, default),
+        None => level <= default,
+    }
+}
+
+pub fn write(level: Level, module: &str, message: fmt::Arguments) {
+    let module = module.split_once("::").map_or(module, |(_, rest)| rest);
+    eprintln!("[{} {}] {}", level, module, message);
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level, module_path!()) {
+            $crate::log::write($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(Level::from_verbosity(0), Level::Warn);
+        assert_eq!(Level::from_verbosity(2), Level::Debug);
+        assert_eq!(Level::from_verbosity(7), Level::Trace);
+        assert!(Level::Error < Level::Trace);
+        assert_eq!("Debug".parse(), Ok(Level::Debug));
+        assert!("loud".parse::<Level>().is_err());
+        // nothing is set from the command line in tests
+        assert!(enabled(Level::Warn, module_path!()));
+        assert!(!enabled(Level::Info, module_path!()));
+    }
+
+    #[test]
+    fn test_filters() {
+        let filters: Vec<Filter> = ["puzzles_2019=info", "crate::puzzles_2019::compute=trace"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let level = |module| level_for(&filters, module, Level::Warn);
+        assert_eq!(level("advent_rust::puzzles_2019::compute"), Level::Trace);
+        assert_eq!(level("advent_rust::puzzles_2019::day_03"), Level::Info);
+        assert_eq!(level("advent_rust::puzzles_2019"), Level::Info);
+        assert_eq!(level("advent_rust::puzzles_2019x"), Level::Warn);
+        assert_eq!(level("advent_rust::grid"), Level::Warn);
+        assert!("compute".parse::<Filter>().is_err());
+        assert!("compute=loud".parse::<Filter>().is_err());
+    }
+}
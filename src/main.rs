@@ -1,11 +1,23 @@
+mod animation;
+mod automaton;
+mod bits;
+mod cycle;
+mod grid;
+mod log;
+mod math;
+mod memo;
+mod ocr;
+mod point;
 mod puzzle_input;
 mod puzzles_2019;
 mod puzzles_2020;
 mod puzzles_2021;
 mod puzzles_2022;
-
-#[macro_use]
-extern crate lazy_static;
+mod ranges;
+mod render;
+mod scan;
+mod search;
+mod sparse;
 
 #[macro_use]
 extern crate num_derive;
@@ -26,6 +38,38 @@ fn main() {
     let mut opts = Options::new();
     opts.optopt("y", "year", "select a puzzle year", "2019");
     opts.optopt("d", "day", "select a puzzle day", "01");
+    opts.optopt(
+        "p",
+        "param",
+        "a value for puzzles that take one (2019 day 4: a range)",
+        "VALUE",
+    );
+    opts.optopt(
+        "r",
+        "render",
+        "write the final state to a file (.txt, .ans, .ppm, .pgm or .svg)",
+        "FILE",
+    );
+    opts.optopt(
+        "a",
+        "animate",
+        "record each step of a simulation (.cast or .ppm)",
+        "FILE",
+    );
+    opts.optopt("", "frame-skip", "keep one frame out of every N", "N");
+    opts.optopt("", "max-frames", "stop recording after N frames", "N");
+    opts.optflagmulti(
+        "v",
+        "verbose",
+        "show more of what's going on (-vv, -vvv for more)",
+    );
+    opts.optflag("q", "quiet", "only show answers and errors");
+    opts.optmulti(
+        "",
+        "log",
+        "set the log level for one module",
+        "puzzles_2019::compute=trace",
+    );
     opts.optflag("h", "help", "print this help menu");
 
     let matches = match opts.parse(&args[1..]) {
@@ -38,6 +82,20 @@ fn main() {
         print_usage(&program, opts);
         return;
     }
+    if matches.opt_present("q") {
+        log::set_level(log::Level::Error);
+    } else {
+        log::set_level(log::Level::from_verbosity(matches.opt_count("v")));
+    }
+    let filters = matches
+        .opt_strs("log")
+        .iter()
+        .map(|s| s.parse())
+        .collect::<Result<Vec<log::Filter>, _>>()
+        .unwrap_or_else(|e| panic!("{}", e));
+    if !filters.is_empty() {
+        log::set_filters(filters);
+    }
     let year = match matches.opt_str("y") {
         Some(s) => s.parse::<i32>().unwrap(),
         None => 2020,
@@ -46,8 +104,24 @@ fn main() {
         Some(s) => s.parse::<i32>().unwrap(),
         None => 1,
     };
+    if let Some(value) = matches.opt_str("p") {
+        puzzle_input::set_param(&value);
+    }
+    if let Some(path) = matches.opt_str("r") {
+        render::set_target(&path);
+    }
+    if let Some(path) = matches.opt_str("a") {
+        let skip = match matches.opt_str("frame-skip") {
+            Some(s) => s.parse::<usize>().unwrap(),
+            None => 1,
+        };
+        let max = matches
+            .opt_str("max-frames")
+            .map(|s| s.parse::<usize>().unwrap());
+        animation::start(&path, skip, max);
+    }
 
-    println!("{}", format!("Running {0}:day_{1:02}", year, day));
+    info!("Running {0}:day_{1:02}", year, day);
     match year {
         2019 => puzzles_2019::run(day),
         2020 => puzzles_2020::run(day),
@@ -0,0 +1,141 @@
+// Number theory helpers. Intermediate products are done in i128,
+// so moduli up to i64::MAX are safe.
+
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        (a / gcd(a, b) * b).abs()
+    }
+}
+
+// Least common multiple of all values (1 if there are none)
+pub fn lcm_all<I: IntoIterator<Item = i64>>(values: I) -> i64 {
+    values.into_iter().fold(1, lcm)
+}
+
+// Extended Euclid: (g, x, y) such that a*x + b*y = g = gcd(a, b)
+pub fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+// x such that a*x = 1 (mod m), if a and m are coprime
+#[allow(dead_code)]
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    if g == 1 {
+        Some(x.rem_euclid(m))
+    } else {
+        None
+    }
+}
+
+// base^exp (mod m), by repeated squaring
+#[allow(dead_code)]
+pub fn mod_pow(base: i128, mut exp: u64, m: i128) -> i128 {
+    if m == 1 {
+        return 0;
+    }
+    let mut result = 1;
+    let mut base = base.rem_euclid(m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result
+}
+
+// Chinese Remainder Theorem: find x with x = r (mod m) for every (r, m).
+// Moduli do not need to be coprime. Returns (x, lcm of the moduli)
+// with 0 <= x < lcm, or None if the congruences contradict each other.
+pub fn crt<I: IntoIterator<Item = (i128, i128)>>(congruences: I) -> Option<(i128, i128)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+    for (r, n) in congruences {
+        if n <= 0 {
+            return None;
+        }
+        // x + m*k = r (mod n)  =>  m*k = (r - x) (mod n)
+        let (g, p, _) = egcd(m, n);
+        let diff = r - x;
+        if diff % g != 0 {
+            return None;
+        }
+        let step = n / g;
+        let k = (diff / g).rem_euclid(step) * p.rem_euclid(step) % step;
+        let next = m.checked_mul(step)?;
+        x = (x + m * k).rem_euclid(next);
+        m = next;
+    }
+    Some((x, m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm_all(vec![23, 19, 13, 17]), 96577);
+        assert_eq!(lcm_all(vec![2, 4, 6, 8]), 24);
+        assert_eq!(lcm_all(Vec::new()), 1);
+    }
+
+    #[test]
+    fn test_modular() {
+        let (g, x, y) = egcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2, 0, 7), 1);
+        assert_eq!(mod_pow(-2, 3, 7), 6);
+        assert_eq!(mod_pow(7, 20201227, 20201227), 7);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(vec![(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // not coprime, but consistent
+        assert_eq!(crt(vec![(2, 4), (4, 6)]), Some((10, 12)));
+        // not coprime, and inconsistent
+        assert_eq!(crt(vec![(1, 4), (2, 6)]), None);
+        assert_eq!(crt(vec![(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(Vec::new()), Some((0, 1)));
+        assert_eq!(crt(vec![(1, 0)]), None);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+// A cache for recursive functions. The function is given the key to solve, and a
+// callback to use for sub-problems, which checks the cache before recursing:
+//
+//   let mut memo = Memo::new();
+//   memo.get(n, &|n, fib| if *n < 2 { *n } else { fib(n - 1) + fib(n - 2) })
+
+#[derive(Clone, Debug, Default)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new() -> Memo<K, V> {
+        Memo {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get<F>(&mut self, key: K, f: &F) -> V
+    where
+        F: Fn(&K, &mut dyn FnMut(K) -> V) -> V,
+    {
+        if let Some(v) = self.cache.get(&key) {
+            self.hits += 1;
+            return v.clone();
+        }
+        self.misses += 1;
+        let v = f(&key, &mut |k| self.get(k, f));
+        self.cache.insert(key, v.clone());
+        v
+    }
+
+    // Lookups answered from the cache
+    #[allow(dead_code)]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    // Lookups that had to be computed (one per cached value)
+    #[allow(dead_code)]
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+impl<K, V> fmt::Display for Memo<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.hits + self.misses;
+        let rate = if total == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f64 / total as f64
+        };
+        write!(
+            f,
+            "Memo: {} entries, {} hits, {} misses ({:.1}% hit rate)",
+            self.cache.len(),
+            self.hits,
+            self.misses,
+            rate
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fibonacci() {
+        let mut memo: Memo<u64, u64> = Memo::new();
+        let fib = |n: &u64, fib: &mut dyn FnMut(u64) -> u64| {
+            if *n < 2 {
+                *n
+            } else {
+                fib(n - 1) + fib(n - 2)
+            }
+        };
+        assert_eq!(memo.get(90, &fib), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(memo.misses(), 91);
+        assert_eq!(memo.hits(), 88);
+
+        // answered from the cache
+        assert_eq!(memo.get(50, &fib), 12586269025);
+        assert_eq!(memo.hits(), 89);
+        assert_eq!(
+            memo.to_string(),
+            "Memo: 91 entries, 89 hits, 91 misses (49.4% hit rate)"
+        );
+
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+
+    #[test]
+    fn test_grid_paths() {
+        // lattice paths from (r, c) to (0, 0), moving up or left
+        let mut memo = Memo::new();
+        let paths = memo.get((16, 16), &|(r, c): &(u64, u64), paths| {
+            if *r == 0 || *c == 0 {
+                1
+            } else {
+                paths((r - 1, *c)) + paths((*r, c - 1))
+            }
+        });
+        assert_eq!(paths, 601080390);
+    }
+}
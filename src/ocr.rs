@@ -0,0 +1,159 @@
+use std::fmt;
+
+// Reads the block letters that some puzzles draw as their answer.
+// Letters are 6 rows high, and sit in 5 column cells: 4 columns for most letters,
+// with a blank column between them, but a few letters (like Y) use all 5.
+// In the image, '#' is lit and anything else is dark.
+
+const HEIGHT: usize = 6;
+const CELL: usize = 5;
+
+const FONT: [(char, [&str; HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OcrError {
+    // The image (after trimming blank rows) isn't one line of letters
+    Height(usize),
+    // What could be read ('?' for unknown letters), and the letters that couldn't
+    Unrecognized {
+        partial: String,
+        glyphs: Vec<String>,
+    },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::Height(h) => write!(f, "Expected letters {} rows high, found {}", HEIGHT, h),
+            OcrError::Unrecognized { partial, glyphs } => {
+                writeln!(f, "Unrecognized letters in {:?}:", partial)?;
+                for g in glyphs {
+                    writeln!(f, "{}", g)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn matches(glyph: &[String], letter: &[&str; HEIGHT]) -> bool {
+    glyph.iter().zip(letter.iter()).all(|(g, l)| {
+        // letters narrower than the cell are padded with dark columns
+        g.chars()
+            .zip(l.chars().chain(std::iter::repeat('.')))
+            .all(|(a, b)| a == b)
+    })
+}
+
+pub fn recognize(image: &str) -> Result<String, OcrError> {
+    let rows: Vec<Vec<bool>> = image
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .skip_while(|row: &Vec<bool>| !row.contains(&true))
+        .collect();
+    let lit = rows.iter().rposition(|row| row.contains(&true));
+    let rows = &rows[..lit.map_or(0, |i| i + 1)];
+    if rows.len() != HEIGHT {
+        return Err(OcrError::Height(rows.len()));
+    }
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut partial = String::new();
+    let mut glyphs = Vec::new();
+    for x in (0..width).step_by(CELL) {
+        let glyph: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                (x..x + CELL)
+                    .map(|i| if row.get(i) == Some(&true) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        if glyph.iter().all(|row| !row.contains('#')) {
+            continue;
+        }
+        match FONT.iter().find(|(_, letter)| matches(&glyph, letter)) {
+            Some((c, _)) => partial.push(*c),
+            None => {
+                partial.push('?');
+                glyphs.push(glyph.join("\n"));
+            }
+        }
+    }
+
+    if glyphs.is_empty() {
+        Ok(partial)
+    } else {
+        Err(OcrError::Unrecognized { partial, glyphs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draw a word with the font, with a blank row above and below
+    fn draw(word: &str) -> String {
+        let blank = ".".repeat(CELL * word.len());
+        let mut rows = vec![blank.clone()];
+        for y in 0..HEIGHT {
+            let mut row = String::new();
+            for c in word.chars() {
+                let (_, letter) = FONT.iter().find(|(l, _)| *l == c).unwrap();
+                row.push_str(&format!("{:.<5}", letter[y]));
+            }
+            rows.push(row);
+        }
+        rows.push(blank);
+        rows.join("\n")
+    }
+
+    #[test]
+    fn test_recognize() {
+        let alphabet: String = FONT.iter().map(|(c, _)| *c).collect();
+        assert_eq!(recognize(&draw(&alphabet)), Ok(alphabet));
+        assert_eq!(recognize(&draw("RYZ")), Ok("RYZ".to_string()));
+
+        // dark cells drawn with spaces, lit cells with '#'
+        assert_eq!(
+            recognize(" ##  #  # \n#  # #  # \n#  # #### \n#### #  # \n#  # #  # \n#  # #  # "),
+            Ok("AH".to_string())
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(recognize("####\n#..#"), Err(OcrError::Height(2)));
+
+        let err = recognize("#.#.\n.#..\n#.#.\n.#..\n#.#.\n.#..").unwrap_err();
+        assert_eq!(
+            err,
+            OcrError::Unrecognized {
+                partial: "?".to_string(),
+                glyphs: vec!["#.#..\n.#...\n#.#..\n.#...\n#.#..\n.#...".to_string()],
+            }
+        );
+        assert!(err
+            .to_string()
+            .starts_with("Unrecognized letters in \"?\":\n#.#.."));
+    }
+}
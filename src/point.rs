@@ -0,0 +1,351 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+
+// Integer points (or vectors) in N dimensions, with cartesian semantics:
+// in 2D, x grows to the right (east) and y grows up (north).
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize>(pub [i32; N]);
+
+pub type Point2 = Point<2>;
+#[allow(dead_code)]
+pub type Point3 = Point<3>;
+#[allow(dead_code)]
+pub type Point4 = Point<4>;
+
+impl<const N: usize> Point<N> {
+    pub const ORIGIN: Point<N> = Point([0; N]);
+
+    pub fn manhattan(&self, other: &Point<N>) -> i32 {
+        (*self - *other).0.iter().map(|v| v.abs()).sum()
+    }
+
+    pub fn chebyshev(&self, other: &Point<N>) -> i32 {
+        (*self - *other)
+            .0
+            .iter()
+            .map(|v| v.abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Each coordinate reduced to -1, 0 or 1: a single step toward the same direction
+    pub fn signum(&self) -> Point<N> {
+        self.map(|v| v.signum())
+    }
+
+    pub fn map<F: Fn(i32) -> i32>(&self, f: F) -> Point<N> {
+        let mut result = *self;
+        result.0.iter_mut().for_each(|v| *v = f(*v));
+        result
+    }
+
+    // All 3^N - 1 points that touch this one, including diagonals
+    #[allow(dead_code)]
+    pub fn neighbors(&self) -> Vec<Point<N>> {
+        let total = 3usize.pow(N as u32);
+        let mut result = Vec::with_capacity(total - 1);
+        for i in 0..total {
+            let mut offset = [0; N];
+            let mut rest = i;
+            for v in offset.iter_mut() {
+                *v = (rest % 3) as i32 - 1;
+                rest /= 3;
+            }
+            if offset != [0; N] {
+                result.push(*self + Point(offset));
+            }
+        }
+        result
+    }
+
+    // The 2N points one step away along a single axis
+    #[allow(dead_code)]
+    pub fn orthogonal(&self) -> Vec<Point<N>> {
+        let mut result = Vec::with_capacity(2 * N);
+        for axis in 0..N {
+            for d in [-1, 1] {
+                let mut p = *self;
+                p.0[axis] += d;
+                result.push(p);
+            }
+        }
+        result
+    }
+}
+
+impl Point<2> {
+    pub fn new(x: i32, y: i32) -> Point2 {
+        Point([x, y])
+    }
+
+    pub fn x(&self) -> i32 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i32 {
+        self.0[1]
+    }
+
+    // Rotate around the origin by 90 degree steps (counter-clockwise)
+    pub fn rotate_left(&self, steps: i32) -> Point2 {
+        match steps.rem_euclid(4) {
+            0 => *self,
+            1 => Point2::new(-self.y(), self.x()),
+            2 => Point2::new(-self.x(), -self.y()),
+            _ => Point2::new(self.y(), -self.x()),
+        }
+    }
+
+    // Rotate around the origin by 90 degree steps (clockwise)
+    pub fn rotate_right(&self, steps: i32) -> Point2 {
+        self.rotate_left(-steps)
+    }
+}
+
+impl Point<3> {
+    #[allow(dead_code)]
+    pub fn new(x: i32, y: i32, z: i32) -> Point3 {
+        Point([x, y, z])
+    }
+}
+
+impl Point<4> {
+    #[allow(dead_code)]
+    pub fn new(x: i32, y: i32, z: i32, w: i32) -> Point4 {
+        Point([x, y, z, w])
+    }
+}
+
+impl From<(i32, i32)> for Point2 {
+    fn from(p: (i32, i32)) -> Point2 {
+        Point([p.0, p.1])
+    }
+}
+
+impl<const N: usize> Default for Point<N> {
+    fn default() -> Self {
+        Point::ORIGIN
+    }
+}
+
+impl<const N: usize> fmt::Display for Point<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, v) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", v)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<const N: usize> ops::Index<usize> for Point<N> {
+    type Output = i32;
+
+    fn index(&self, axis: usize) -> &i32 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> ops::IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i32 {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> ops::Add for Point<N> {
+    type Output = Point<N>;
+
+    fn add(mut self, rhs: Point<N>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<const N: usize> ops::AddAssign for Point<N> {
+    fn add_assign(&mut self, rhs: Point<N>) {
+        self.0
+            .iter_mut()
+            .zip(rhs.0.iter())
+            .for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<const N: usize> ops::Sub for Point<N> {
+    type Output = Point<N>;
+
+    fn sub(mut self, rhs: Point<N>) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const N: usize> ops::SubAssign for Point<N> {
+    fn sub_assign(&mut self, rhs: Point<N>) {
+        self.0
+            .iter_mut()
+            .zip(rhs.0.iter())
+            .for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl<const N: usize> ops::Mul<i32> for Point<N> {
+    type Output = Point<N>;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        self.map(|v| v * rhs)
+    }
+}
+
+impl<const N: usize> ops::Neg for Point<N> {
+    type Output = Point<N>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|v| -v)
+    }
+}
+
+// Compass directions, in clockwise order.
+// U/D/L/R are accepted as aliases for N/S/W/E.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn offset(&self) -> Point2 {
+        match self {
+            Direction::North => Point2::new(0, 1),
+            Direction::East => Point2::new(1, 0),
+            Direction::South => Point2::new(0, -1),
+            Direction::West => Point2::new(-1, 0),
+        }
+    }
+
+    // Turn clockwise by 90 degree steps (negative to turn counter-clockwise)
+    pub fn turn_right(&self, steps: i32) -> Direction {
+        let i = Direction::ALL.iter().position(|d| d == self).unwrap() as i32;
+        Direction::ALL[(i + steps).rem_euclid(4) as usize]
+    }
+
+    pub fn turn_left(&self, steps: i32) -> Direction {
+        self.turn_right(-steps)
+    }
+
+    #[allow(dead_code)]
+    pub fn reverse(&self) -> Direction {
+        self.turn_right(2)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Direction, String> {
+        match c {
+            'N' | 'U' => Ok(Direction::North),
+            'E' | 'R' => Ok(Direction::East),
+            'S' | 'D' => Ok(Direction::South),
+            'W' | 'L' => Ok(Direction::West),
+            _ => Err(format!("Unknown direction: {:?}", c)),
+        }
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Direction, String> {
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            _ => Err(format!("Unknown direction: {:?}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point2::new(3, -4);
+        let b = Point2::new(1, 2);
+        assert_eq!(a + b, Point2::new(4, -2));
+        assert_eq!(a - b, Point2::new(2, -6));
+        assert_eq!(a * 3, Point2::new(9, -12));
+        assert_eq!(-a, Point2::new(-3, 4));
+        assert_eq!(a.signum(), Point2::new(1, -1));
+        assert_eq!(Point2::from((3, -4)), a);
+        assert_eq!(a.to_string(), "(3,-4)");
+
+        let c = Point4::new(1, 2, 3, 4);
+        assert_eq!(c + c, Point4::new(2, 4, 6, 8));
+        assert_eq!(c[3], 4);
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Point3::new(1, -2, 3);
+        assert_eq!(a.manhattan(&Point::ORIGIN), 6);
+        assert_eq!(a.chebyshev(&Point::ORIGIN), 3);
+        assert_eq!(a.manhattan(&a), 0);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let p = Point2::new(10, 4);
+        assert_eq!(p.rotate_right(1), Point2::new(4, -10));
+        assert_eq!(p.rotate_right(2), Point2::new(-10, -4));
+        assert_eq!(p.rotate_right(3), Point2::new(-4, 10));
+        assert_eq!(p.rotate_left(1), Point2::new(-4, 10));
+        assert_eq!(p.rotate_left(4), p);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(Point2::ORIGIN.neighbors().len(), 8);
+        assert_eq!(Point3::ORIGIN.neighbors().len(), 26);
+        assert_eq!(Point4::ORIGIN.neighbors().len(), 80);
+        assert!(Point3::ORIGIN
+            .neighbors()
+            .iter()
+            .all(|p| p.chebyshev(&Point::ORIGIN) == 1));
+
+        let p = Point2::new(5, 5);
+        let o = p.orthogonal();
+        assert_eq!(o.len(), 4);
+        assert!(o.contains(&Point2::new(5, 6)));
+        assert!(o.iter().all(|n| n.manhattan(&p) == 1));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::try_from('U'), Ok(Direction::North));
+        assert_eq!(Direction::try_from('W'), Ok(Direction::West));
+        assert_eq!("R".parse::<Direction>(), Ok(Direction::East));
+        assert!("X".parse::<Direction>().is_err());
+        assert!("NE".parse::<Direction>().is_err());
+
+        assert_eq!(Direction::East.turn_right(1), Direction::South);
+        assert_eq!(Direction::North.turn_right(2), Direction::South);
+        assert_eq!(Direction::South.turn_left(3), Direction::West);
+        assert_eq!(Direction::West.reverse(), Direction::East);
+        assert_eq!(Direction::South.offset(), Point2::new(0, -1));
+    }
+}
@@ -2,6 +2,21 @@ use std::fs;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::sync::OnceLock;
+
+static PARAM: OnceLock<String> = OnceLock::new();
+
+// A value given on the command line, for puzzles that can take one
+// in place of (or on top of) their input file
+pub fn set_param(value: &str) {
+    PARAM
+        .set(value.to_string())
+        .unwrap_or_else(|_| panic!("Puzzle parameter is already set"));
+}
+
+pub fn param() -> Option<&'static str> {
+    PARAM.get().map(|s| s.as_str())
+}
 
 pub fn read_all_lines<P>(filename: P) -> Vec<String>
 where
@@ -1,3 +1,4 @@
+mod analyze;
 mod compute;
 mod day_01;
 mod day_02;
@@ -9,6 +10,11 @@ mod day_07;
 mod day_08;
 mod day_09;
 mod day_10;
+mod params;
+mod pipeline;
+mod sif;
+
+use crate::warn;
 
 pub fn run(day: i32) {
     match day {
@@ -24,6 +30,6 @@ pub fn run(day: i32) {
         10 => day_10::run(),
 
         // Handle the rest of cases
-        _ => println!("Nothing to see here"),
+        _ => warn!("Nothing to see here"),
     }
 }
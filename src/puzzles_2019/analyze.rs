@@ -0,0 +1,431 @@
+use super::compute::{get_parameters, Mode};
+use petgraph::graphmap::DiGraphMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+// Static analysis of an Intcode program: decode reachable instructions,
+// split them into basic blocks at jump targets, and connect the blocks
+// into a control-flow graph.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+    pub addr: usize,
+    pub op: i32,
+    pub params: Vec<(Mode, i64)>,
+}
+impl Instruction {
+    pub fn length(&self) -> usize {
+        1 + self.params.len()
+    }
+
+    fn is_jump(&self) -> bool {
+        self.op == 5 || self.op == 6
+    }
+
+    fn is_halt(&self) -> bool {
+        self.op == 99
+    }
+
+    fn mnemonic(&self) -> &'static str {
+        match self.op {
+            1 => "add",
+            2 => "mul",
+            3 => "in",
+            4 => "out",
+            5 => "jnz",
+            6 => "jz",
+            7 => "lt",
+            8 => "eq",
+            9 => "arb",
+            _ => "hlt",
+        }
+    }
+
+    // Address written by this instruction, if it writes to a fixed position.
+    // Relative mode stores depend on the relative base at runtime.
+    fn store_target(&self) -> Option<(Mode, i64)> {
+        match self.op {
+            1 | 2 | 7 | 8 => Some(self.params[2]),
+            3 => Some(self.params[0]),
+            _ => None,
+        }
+    }
+
+    // Where a jump goes, and whether execution can continue with the next instruction.
+    // Only immediate mode targets can be resolved without running the program.
+    fn branches(&self) -> (Option<usize>, bool) {
+        if !self.is_jump() {
+            return (None, !self.is_halt());
+        }
+        let target = match self.params[1] {
+            (Mode::Immediate, t) if t >= 0 => Some(t as usize),
+            _ => None,
+        };
+        // 1105,1,x and 1106,0,x are unconditional jumps
+        let always = match self.params[0] {
+            (Mode::Immediate, c) => (self.op == 5) == (c != 0),
+            _ => false,
+        };
+        (target, !always)
+    }
+}
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}: {}", self.addr, self.mnemonic())?;
+        for (i, (mode, value)) in self.params.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            match mode {
+                Mode::Position => write!(f, "{}[{}]", sep, value)?,
+                Mode::Immediate => write!(f, "{}{}", sep, value)?,
+                Mode::Relative => write!(f, "{}[rb{:+}]", sep, value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    Next,
+    Jump,
+}
+
+#[derive(Clone, Debug)]
+pub struct BasicBlock {
+    pub start: usize,
+    pub instructions: Vec<Instruction>,
+    // block ends with a jump whose target is only known at runtime
+    pub indirect: bool,
+}
+#[allow(dead_code)]
+impl BasicBlock {
+    pub fn end(&self) -> usize {
+        let last = self.instructions.last().unwrap();
+        last.addr + last.length()
+    }
+}
+
+// A store (at `addr`) into a word (`target`) that belongs to the instruction at `instruction`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelfModification {
+    pub addr: usize,
+    pub target: usize,
+    pub instruction: usize,
+}
+
+#[derive(Debug)]
+pub struct Analysis {
+    pub blocks: BTreeMap<usize, BasicBlock>,
+    pub graph: DiGraphMap<usize, Edge>,
+    pub self_modifying: Vec<SelfModification>,
+    // relative mode stores that could land anywhere, including code
+    pub dynamic_stores: Vec<usize>,
+    // reachable addresses that do not hold a valid instruction
+    pub invalid: Vec<usize>,
+}
+
+fn opcode_length(op: i32) -> Option<usize> {
+    match op {
+        1 | 2 | 7 | 8 => Some(4),
+        5 | 6 => Some(3),
+        3 | 4 | 9 => Some(2),
+        99 => Some(1),
+        _ => None,
+    }
+}
+
+// Unlike compute::get_parameters, this does not panic: data is often
+// reachable by a naive sweep, and should just fail to decode.
+pub fn decode(codes: &[i64], addr: usize) -> Option<Instruction> {
+    let value = *codes.get(addr)?;
+    if !(0..30000).contains(&value) {
+        return None;
+    }
+    let len = opcode_length((value % 100) as i32)?;
+    let digits = value / 100;
+    if digits % 10 > 2 || digits / 10 % 10 > 2 || digits / 100 > 2 || addr + len > codes.len() {
+        return None;
+    }
+
+    let (modes, op) = get_parameters(value);
+    let modes = [modes.2, modes.1, modes.0];
+    let params: Vec<(Mode, i64)> = (1..len).map(|i| (modes[i - 1], codes[addr + i])).collect();
+
+    // writes are never immediate
+    let inst = Instruction { addr, op, params };
+    if let Some((Mode::Immediate, _)) = inst.store_target() {
+        return None;
+    }
+    Some(inst)
+}
+
+pub fn analyze(codes: &[i64]) -> Analysis {
+    // Find every instruction reachable from 0 by following fall-through and immediate jumps
+    let mut instructions: BTreeMap<usize, Instruction> = BTreeMap::new();
+    let mut leaders: BTreeSet<usize> = BTreeSet::new();
+    let mut invalid: BTreeSet<usize> = BTreeSet::new();
+    let mut pending = vec![0];
+    leaders.insert(0);
+
+    while let Some(addr) = pending.pop() {
+        if instructions.contains_key(&addr) || invalid.contains(&addr) {
+            continue;
+        }
+        let inst = match decode(codes, addr) {
+            Some(inst) => inst,
+            None => {
+                invalid.insert(addr);
+                continue;
+            }
+        };
+        let (target, next) = inst.branches();
+        if let Some(t) = target {
+            leaders.insert(t);
+            pending.push(t);
+        }
+        if next {
+            if inst.is_jump() {
+                leaders.insert(addr + inst.length());
+            }
+            pending.push(addr + inst.length());
+        }
+        instructions.insert(addr, inst);
+    }
+
+    // Split the reachable instructions into blocks
+    let mut blocks: BTreeMap<usize, BasicBlock> = BTreeMap::new();
+    for start in leaders.iter().filter(|a| instructions.contains_key(a)) {
+        let mut block = BasicBlock {
+            start: *start,
+            instructions: Vec::new(),
+            indirect: false,
+        };
+        let mut addr = *start;
+        while let Some(inst) = instructions.get(&addr) {
+            block.instructions.push(inst.clone());
+            addr += inst.length();
+            if inst.is_jump() || inst.is_halt() || leaders.contains(&addr) {
+                break;
+            }
+        }
+        blocks.insert(*start, block);
+    }
+
+    let mut graph = DiGraphMap::new();
+    for block in blocks.values_mut() {
+        graph.add_node(block.start);
+        let last = block.instructions.last().unwrap().clone();
+        let (target, next) = last.branches();
+        if last.is_jump() && target.is_none() {
+            block.indirect = true;
+        }
+        if let Some(t) = target.filter(|t| instructions.contains_key(t)) {
+            graph.add_edge(block.start, t, Edge::Jump);
+        }
+        let following = last.addr + last.length();
+        // a conditional jump to the next instruction is still a jump
+        if next
+            && instructions.contains_key(&following)
+            && !graph.contains_edge(block.start, following)
+        {
+            graph.add_edge(block.start, following, Edge::Next);
+        }
+    }
+
+    // Any fixed-position store into a word that is decoded as code
+    let mut code: BTreeMap<usize, usize> = BTreeMap::new();
+    for inst in instructions.values() {
+        for a in inst.addr..inst.addr + inst.length() {
+            code.insert(a, inst.addr);
+        }
+    }
+    let mut self_modifying = Vec::new();
+    let mut dynamic_stores = Vec::new();
+    for inst in instructions.values() {
+        match inst.store_target() {
+            Some((Mode::Position, t)) if t >= 0 => {
+                if let Some(owner) = code.get(&(t as usize)) {
+                    self_modifying.push(SelfModification {
+                        addr: inst.addr,
+                        target: t as usize,
+                        instruction: *owner,
+                    });
+                }
+            }
+            Some((Mode::Relative, _)) => dynamic_stores.push(inst.addr),
+            _ => {}
+        }
+    }
+
+    Analysis {
+        blocks,
+        graph,
+        self_modifying,
+        dynamic_stores,
+        invalid: invalid.into_iter().collect(),
+    }
+}
+
+impl Analysis {
+    // Blocks that can not be reached from the entry point through known edges
+    // (they are reachable only via indirect jumps)
+    pub fn unreachable_blocks(&self) -> Vec<usize> {
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut pending = vec![0];
+        while let Some(n) = pending.pop() {
+            if self.graph.contains_node(n) && seen.insert(n) {
+                pending.extend(self.graph.neighbors(n));
+            }
+        }
+        self.blocks
+            .keys()
+            .filter(|k| !seen.contains(k))
+            .copied()
+            .collect()
+    }
+
+    // Graphviz DOT: one record per block containing its disassembly
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph intcode {\n    node [shape=box, fontname=monospace];\n");
+        for block in self.blocks.values() {
+            let label: Vec<String> = block.instructions.iter().map(|i| i.to_string()).collect();
+            let modified = self
+                .self_modifying
+                .iter()
+                .any(|m| m.target >= block.start && m.target < block.end());
+            let style = if modified { ", color=red" } else { "" };
+            dot.push_str(&format!(
+                "    b{} [label=\"{}\\l\"{}];\n",
+                block.start,
+                label.join("\\l"),
+                style
+            ));
+        }
+        for (from, to, edge) in self.graph.all_edges() {
+            let style = match edge {
+                Edge::Jump => " [label=\"jump\"]",
+                Edge::Next => "",
+            };
+            dot.push_str(&format!("    b{} -> b{}{};\n", from, to, style));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} blocks, {} edges, {} self-modifying stores, {} relative stores",
+            self.blocks.len(),
+            self.graph.edge_count(),
+            self.self_modifying.len(),
+            self.dynamic_stores.len()
+        )?;
+        for m in &self.self_modifying {
+            writeln!(
+                f,
+                "  {:04} writes {:04} (instruction at {:04})",
+                m.addr, m.target, m.instruction
+            )?;
+        }
+        for block in self.blocks.values().filter(|b| b.indirect) {
+            writeln!(f, "  block {:04} ends with an indirect jump", block.start)?;
+        }
+        for addr in &self.invalid {
+            writeln!(f, "  {:04} is not a valid instruction", addr)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<i64> {
+        input
+            .split(',')
+            .map(|x| x.trim().parse::<i64>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_decode() {
+        let codes = parse("1002,4,3,4,33");
+        let inst = decode(&codes, 0).unwrap();
+        assert_eq!(inst.op, 2);
+        assert_eq!(inst.to_string(), "0000: mul [4], 3, [4]");
+        assert_eq!(decode(&codes, 4), None);
+        assert_eq!(decode(&parse("1101,1,1"), 0), None);
+        assert_eq!(decode(&parse("11101,1,1,0"), 0), None);
+    }
+
+    #[test]
+    fn test_straight_line_self_modifying() {
+        let a = analyze(&parse("1,1,1,4,99,5,6,0,99"));
+        assert_eq!(a.blocks.len(), 1);
+        assert_eq!(a.graph.edge_count(), 0);
+        assert_eq!(
+            a.self_modifying,
+            vec![SelfModification {
+                addr: 0,
+                target: 4,
+                instruction: 4
+            }]
+        );
+    }
+
+    #[test]
+    fn test_jumps() {
+        // output 1 for non-zero input, 0 otherwise
+        let a = analyze(&parse("3,13,1006,13,10,104,1,1105,1,12,104,0,99,0"));
+        assert_eq!(
+            a.blocks.keys().copied().collect::<Vec<_>>(),
+            vec![0, 5, 10, 12]
+        );
+        assert_eq!(a.graph.edge_weight(0, 10), Some(&Edge::Jump));
+        assert_eq!(a.graph.edge_weight(0, 5), Some(&Edge::Next));
+        assert_eq!(a.graph.edge_weight(5, 12), Some(&Edge::Jump));
+        assert_eq!(a.graph.edge_weight(10, 12), Some(&Edge::Next));
+        assert_eq!(a.graph.edge_count(), 4);
+        assert!(a.invalid.is_empty());
+        assert!(a.self_modifying.is_empty());
+
+        let a = analyze(&parse(
+            "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,
+            1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,
+            999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99",
+        ));
+        assert_eq!(
+            a.blocks.keys().copied().collect::<Vec<_>>(),
+            vec![0, 9, 16, 22, 31, 36, 46]
+        );
+        assert_eq!(a.graph.edge_weight(0, 22), Some(&Edge::Jump));
+        assert_eq!(a.graph.edge_weight(0, 9), Some(&Edge::Next));
+        assert_eq!(a.graph.edge_weight(16, 36), Some(&Edge::Jump));
+        assert_eq!(a.graph.edge_weight(16, 19), None);
+        assert!(a.unreachable_blocks().is_empty());
+        assert!(a.invalid.is_empty());
+    }
+
+    #[test]
+    fn test_indirect_jump() {
+        let a = analyze(&parse("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9"));
+        assert!(a.blocks[&0].indirect);
+        assert_eq!(a.graph.edge_weight(0, 5), Some(&Edge::Next));
+        assert_eq!(a.unreachable_blocks(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let a = analyze(&parse("1105,1,4,99,104,7,99"));
+        let dot = a.to_dot();
+        assert!(dot.starts_with("digraph intcode {"));
+        assert!(dot.contains("b0 -> b4 [label=\"jump\"];"));
+        assert!(dot.contains("b4 [label=\"0004: out 7\\l0006: hlt\\l\"];"));
+        assert!(!dot.contains("b3"));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::log::{self, Level};
+use crate::{error, trace};
 use std::collections::HashMap;
 
 pub trait ProgramIO {
@@ -6,6 +8,11 @@ pub trait ProgramIO {
 
     fn write_output(&mut self, value: i64);
     fn read_output(&self) -> i64;
+
+    // A Machine pauses (rather than calling take_input) when this is false
+    fn has_input(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -23,6 +30,11 @@ impl DefaultProgramIO {
             all: Vec::new(),
         }
     }
+
+    // Every value written, in order
+    pub fn outputs(&self) -> &[i64] {
+        &self.all
+    }
 }
 
 impl ProgramIO for DefaultProgramIO {
@@ -44,8 +56,8 @@ impl ProgramIO for DefaultProgramIO {
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum Mode {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Mode {
     Position,  // 0
     Immediate, // 1, not allowed for writes
     Relative,  // 2
@@ -61,16 +73,18 @@ fn to_mode(x: i32) -> Mode {
 }
 
 // A tuple struct
-struct Modes(Mode, Mode, Mode);
+pub(super) struct Modes(pub Mode, pub Mode, pub Mode);
 
 struct Computer<'a> {
     codes: &'a mut Vec<i64>,
     io: &'a mut dyn ProgramIO,
     extents: HashMap<usize, i64>,
     relative_base: usize,
+    // Whether to trace each instruction, checked once when execute starts
+    tracing: bool,
 }
 
-fn get_parameters(input: i64) -> (Modes, i32) {
+pub(super) fn get_parameters(input: i64) -> (Modes, i32) {
     let mut x = input as i32;
     let op = x % 100;
     x /= 100;
@@ -90,27 +104,46 @@ fn get_parameters(input: i64) -> (Modes, i32) {
 //    The address a relative mode parameter refers to is itself plus the current relative base.
 fn get_index(computer: &mut Computer, mode: Mode, i: usize) -> usize {
     if mode == Mode::Position {
-        //println!("- {:?} mode from {}: {:?}", mode, i, computer.codes[i] as usize);
+        if computer.tracing {
+            trace!(
+                "- {:?} mode from {}: {:?}",
+                mode,
+                i,
+                computer.codes[i] as usize
+            );
+        }
         computer.codes[i] as usize
     } else {
         // keep as i64 to allow negative numbers for addition
         let x: i64 = computer.codes[i] + computer.relative_base as i64;
-        // println!("- {:?} mode from {} with relative base {:?}: {:?} --> {:?}", mode, i,
-        //     computer.relative_base, computer.codes[i], x);
+        if computer.tracing {
+            trace!(
+                "- {:?} mode from {} with relative base {:?}: {:?} --> {:?}",
+                mode,
+                i,
+                computer.relative_base,
+                computer.codes[i],
+                x
+            );
+        }
         x as usize
     }
 }
 
 fn read(computer: &mut Computer, mode: Mode, i: usize) -> i64 {
     if mode == Mode::Immediate {
-        //println!("- {:?} mode from {}: {}", mode, i, computer.codes[i]);
+        if computer.tracing {
+            trace!("- {:?} mode from {}: {}", mode, i, computer.codes[i]);
+        }
         return computer.codes[i];
     }
 
     let ix = get_index(computer, mode, i);
 
     if ix >= computer.codes.len() {
-        //println!("<-- {:?}:{:?}", ix, computer.extents.get(&ix));
+        if computer.tracing {
+            trace!("<-- {:?}:{:?}", ix, computer.extents.get(&ix));
+        }
         match computer.extents.get(&ix) {
             Some(value) => *value,
             None => 0,
@@ -129,7 +162,9 @@ fn store(computer: &mut Computer, mode: Mode, i: usize, value: i64) {
     }
     let ix = get_index(computer, mode, i);
     if ix >= computer.codes.len() {
-        //println!("--> {:?}:{:?} .. {}", ix, computer.extents.get(&ix), value);
+        if computer.tracing {
+            trace!("--> {:?}:{:?} .. {}", ix, computer.extents.get(&ix), value);
+        }
         computer.extents.insert(ix, value);
     } else {
         computer.codes[ix] = value;
@@ -235,36 +270,101 @@ fn opcode_9(modes: Modes, i: usize, computer: &mut Computer) -> usize {
     i + 2 // advance 2: 1 opcode + 1 parameter
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Halted,
+    Waiting, // paused on an input instruction with no input available
+}
+
+fn execute(computer: &mut Computer, start: usize, pause: bool) -> (usize, Status) {
+    computer.tracing = log::enabled(Level::Trace, module_path!());
+    let mut i = start;
+    loop {
+        let (modes, op) = get_parameters(computer.codes[i]);
+        if computer.tracing {
+            trace!(
+                "{:?} === {:?},{:?},{:?} {:?}",
+                computer.codes[i],
+                modes.0,
+                modes.1,
+                modes.2,
+                op
+            );
+        }
+        match op {
+            1 => i = opcode_1(modes, i, computer),
+            2 => i = opcode_2(modes, i, computer),
+            3 if pause && !computer.io.has_input() => return (i, Status::Waiting),
+            3 => i = opcode_3(modes, i, computer),
+            4 => i = opcode_4(modes, i, computer),
+            5 => i = opcode_5(modes, i, computer),
+            6 => i = opcode_6(modes, i, computer),
+            7 => i = opcode_7(modes, i, computer),
+            8 => i = opcode_8(modes, i, computer),
+            9 => i = opcode_9(modes, i, computer),
+            99 => return (i, Status::Halted),
+            _ => {
+                error!("{0} Unknown at index {1}", computer.codes[i], i);
+                return (i, Status::Halted);
+            }
+        }
+    }
+}
+
 pub fn run(codes: &mut Vec<i64>, io: &mut dyn ProgramIO) {
-    let mut i: usize = 0;
     let mut computer = Computer {
         codes,
         io,
         extents: HashMap::new(),
         relative_base: 0,
+        tracing: false,
     };
+    execute(&mut computer, 0, false);
+}
 
-    loop {
-        let (modes, op) = get_parameters(computer.codes[i]);
-        //println!("{:?} === {:?},{:?},{:?} {:?}", computer.codes[i], modes.0, modes.1, modes.2, op);
-        match op {
-            1 => i = opcode_1(modes, i, &mut computer),
-            2 => i = opcode_2(modes, i, &mut computer),
-            3 => i = opcode_3(modes, i, &mut computer),
-            4 => i = opcode_4(modes, i, &mut computer),
-            5 => i = opcode_5(modes, i, &mut computer),
-            6 => i = opcode_6(modes, i, &mut computer),
-            7 => i = opcode_7(modes, i, &mut computer),
-            8 => i = opcode_8(modes, i, &mut computer),
-            9 => i = opcode_9(modes, i, &mut computer),
-            99 => {
-                break;
-            }
-            _ => {
-                println!("ERROR: {0} Unknown at index {1}", codes[i], i);
-                break;
-            }
+// A program that keeps its state between runs, so it can be paused
+// when it needs input and resumed once input has been provided.
+#[derive(Clone, Debug)]
+pub struct Machine {
+    codes: Vec<i64>,
+    extents: HashMap<usize, i64>,
+    relative_base: usize,
+    ip: usize,
+    status: Option<Status>,
+}
+
+impl Machine {
+    pub fn new(program: &[i64]) -> Machine {
+        Machine {
+            codes: program.to_vec(),
+            extents: HashMap::new(),
+            relative_base: 0,
+            ip: 0,
+            status: None,
+        }
+    }
+
+    pub fn halted(&self) -> bool {
+        self.status == Some(Status::Halted)
+    }
+
+    pub fn resume(&mut self, io: &mut dyn ProgramIO) -> Status {
+        if self.halted() {
+            return Status::Halted;
         }
+        let mut computer = Computer {
+            codes: &mut self.codes,
+            io,
+            extents: std::mem::take(&mut self.extents),
+            relative_base: self.relative_base,
+            tracing: false,
+        };
+        let (ip, status) = execute(&mut computer, self.ip, true);
+        self.extents = computer.extents;
+        self.relative_base = computer.relative_base;
+        self.ip = ip;
+        self.status = Some(status);
+        status
     }
 }
 
@@ -493,4 +593,40 @@ mod tests {
         intcode_program(instr, &mut io);
         assert_eq!(io.output, 1125899906842624);
     }
+
+    #[test]
+    fn test_machine_pauses_for_input() {
+        struct QueueIO(Vec<i64>, Vec<i64>);
+        impl ProgramIO for QueueIO {
+            fn add_input(&mut self, value: i64) {
+                self.0.push(value);
+            }
+            fn take_input(&mut self) -> i64 {
+                self.0.remove(0)
+            }
+            fn write_output(&mut self, value: i64) {
+                self.1.push(value);
+            }
+            fn read_output(&self) -> i64 {
+                *self.1.last().unwrap()
+            }
+            fn has_input(&self) -> bool {
+                !self.0.is_empty()
+            }
+        }
+
+        // echo two inputs, then halt
+        let codes: Vec<i64> = vec![3, 0, 4, 0, 3, 0, 4, 0, 99];
+        let mut machine = Machine::new(&codes);
+        let mut io = QueueIO(vec![], vec![]);
+
+        assert_eq!(machine.resume(&mut io), Status::Waiting);
+        io.add_input(7);
+        assert_eq!(machine.resume(&mut io), Status::Waiting);
+        assert_eq!(io.read_output(), 7);
+        io.add_input(11);
+        assert_eq!(machine.resume(&mut io), Status::Halted);
+        assert!(machine.halted());
+        assert_eq!(io.1, vec![7, 11]);
+    }
 }
@@ -1,8 +1,24 @@
 use crate::puzzle_input;
+use crate::scan::ScanError;
+use crate::{debug, scan};
+use num_traits::PrimInt;
+use std::str::FromStr;
 
 pub fn run() {
-    part_1();
-    part_2();
+    let lines = puzzle_input::read_all_lines("./input/2019-d01-input1.txt");
+    let masses: Vec<u64> = parse_masses(&lines).unwrap_or_else(|e| panic!("What is this? {}", e));
+
+    for (n, (mass, fuel)) in breakdown(&masses, &Formula::MODULE).iter().enumerate() {
+        debug!("module {}: mass {} needs {} fuel", n + 1, mass, fuel);
+    }
+    println!(
+        "** Part 1 Final: {0}",
+        total(&masses, &Formula::MODULE).unwrap()
+    );
+    println!(
+        "** Part 2 Final: {0}",
+        total(&masses, &Formula::WITH_FUEL).unwrap()
+    );
 }
 
 // At the first Go / No Go poll, every Elf is Go until the Fuel Counter-Upper.
@@ -10,56 +26,86 @@ pub fn run() {
 // The Fuel Counter-Upper needs to know the total fuel requirement.
 // To find it, individually calculate the fuel needed for the mass of each module
 // (your puzzle input), then add together all the fuel values.
-fn part_1() {
-    let mut sum = 0;
-    let lines = puzzle_input::read_all_lines("./input/2019-d01-input1.txt");
-    for line in lines {
-        let v = line.parse::<i32>().unwrap();
-        let fuel = calculate_fuel(v);
-        sum += fuel;
-    }
-    println!("** Part 1 Final: {0}", sum);
+
+// Fuel required to launch a given module is based on its mass: divide by the
+// divisor, round down, and subtract the subtractor (never less than zero).
+// Fuel has mass too: a recursive formula adds fuel for the fuel, and so on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Formula {
+    divisor: u32,
+    subtractor: u32,
+    recursive: bool,
 }
 
-fn part_2() {
-    let mut sum = 0;
-    let lines = puzzle_input::read_all_lines("./input/2019-d01-input1.txt");
-    for line in lines {
-        let v = line.parse::<i32>().unwrap();
-        let fuel = calculate_fuel(v);
-        sum += add_additional_fuel(fuel);
+impl Formula {
+    // Part 1: mass / 3 - 2
+    const MODULE: Formula = Formula::new(3, 2, false);
+    // Part 2: and again for the fuel
+    const WITH_FUEL: Formula = Formula::new(3, 2, true);
+
+    const fn new(divisor: u32, subtractor: u32, recursive: bool) -> Formula {
+        // fuel has to weigh less than what it carries, or it never ends
+        assert!(divisor >= 2, "Fuel divisor must be at least 2");
+        Formula {
+            divisor,
+            subtractor,
+            recursive,
+        }
     }
-    println!("** Part 2 Final: {0}", sum);
-}
 
-// Fuel required to launch a given module is based on its mass.
-fn calculate_fuel(mass: i32) -> i32 {
-    // Specifically, to find the fuel required for a module, take its mass,
-    // divide by three, round down, and subtract 2.
-    (mass / 3) - 2
-}
+    // Fuel is always less than the mass that needs it,
+    // so this can't overflow whatever the mass fits in
+    fn fuel<T: PrimInt>(&self, mass: T) -> T {
+        let divisor = T::from(self.divisor).unwrap();
+        let subtractor = T::from(self.subtractor).unwrap_or_else(T::max_value);
 
-fn add_additional_fuel(fuel: i32) -> i32 {
-    let mut sum = 0;
-    let mut result = Some(fuel);
-
-    while let Some(i) = result {
-        if i > 0 {
-            sum += i;
-            result = Some(calculate_fuel(i));
-        } else {
-            // We're done
-            result = None;
+        let mut sum = T::zero();
+        let mut mass = mass;
+        loop {
+            let fuel = mass / divisor;
+            if fuel <= subtractor {
+                return sum;
+            }
+            mass = fuel - subtractor;
+            sum = sum + mass;
+            if !self.recursive {
+                return sum;
+            }
         }
     }
+}
+
+// Masses, one per line (blank lines are skipped)
+fn parse_masses<T: FromStr>(lines: &[String]) -> Result<Vec<T>, ScanError> {
+    scan::lines(lines, |line| {
+        scan!(line.trim(), "{}", T).map(|(mass,)| mass)
+    })
+}
 
-    sum
+// (mass, fuel) for each module
+fn breakdown<T: PrimInt>(masses: &[T], formula: &Formula) -> Vec<(T, T)> {
+    masses.iter().map(|m| (*m, formula.fuel(*m))).collect()
+}
+
+// Fuel for all the modules; None if the total doesn't fit in T
+fn total<T: PrimInt>(masses: &[T], formula: &Formula) -> Option<T> {
+    masses
+        .iter()
+        .try_fold(T::zero(), |sum, m| sum.checked_add(&formula.fuel(*m)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn calculate_fuel(mass: i32) -> i32 {
+        Formula::MODULE.fuel(mass)
+    }
+
+    fn add_additional_fuel(fuel: i32) -> i32 {
+        fuel + Formula::WITH_FUEL.fuel(fuel)
+    }
+
     #[test]
     fn calculate_fuel_works() {
         assert_eq!(calculate_fuel(12), 2);
@@ -74,4 +120,44 @@ mod tests {
         assert_eq!(add_additional_fuel(654), 966);
         assert_eq!(add_additional_fuel(33583), 50346);
     }
+
+    #[test]
+    fn test_formula() {
+        assert_eq!(Formula::WITH_FUEL.fuel(100756u64), 50346);
+        assert_eq!(Formula::MODULE.fuel(5i128), 0);
+        assert_eq!(Formula::MODULE.fuel(-100i128), 0);
+        assert_eq!(Formula::new(2, 0, false).fuel(10u64), 5);
+        assert_eq!(Formula::new(2, 0, true).fuel(10u64), 5 + 2 + 1);
+        assert_eq!(Formula::new(10, u32::MAX, true).fuel(250u8), 0);
+
+        // no overflow near the top of the range
+        let heavy = u64::MAX;
+        assert_eq!(Formula::MODULE.fuel(heavy), heavy / 3 - 2);
+        assert!(Formula::WITH_FUEL.fuel(heavy) < heavy / 2);
+        assert_eq!(total(&[heavy, heavy, heavy, heavy], &Formula::MODULE), None);
+        let heavy = i128::MAX;
+        assert_eq!(total(&[heavy, heavy, heavy, heavy], &Formula::MODULE), None);
+        assert!(total(&[heavy], &Formula::WITH_FUEL).is_some());
+    }
+
+    #[test]
+    fn test_modules() {
+        let lines = puzzle_input::split_string("12\n14\n\n1969\n100756");
+        let masses: Vec<u64> = parse_masses(&lines).unwrap();
+        assert_eq!(
+            breakdown(&masses, &Formula::MODULE),
+            vec![(12, 2), (14, 2), (1969, 654), (100756, 33583)]
+        );
+        assert_eq!(total(&masses, &Formula::MODULE), Some(34241));
+        assert_eq!(total(&masses, &Formula::WITH_FUEL), Some(51316));
+
+        let lines = puzzle_input::split_string("12\n14\nheavy\n-3");
+        let err = parse_masses::<u64>(&lines).unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: can't read \"heavy\" as u64"
+        );
+        assert!(parse_masses::<i128>(&puzzle_input::split_string("-3")).is_ok());
+    }
 }
@@ -1,141 +1,210 @@
+use crate::point::{Direction, Point2};
 use crate::puzzle_input;
-use std::cmp;
-use std::collections::HashSet;
-use std::hash;
+use crate::{debug, info};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
 
 pub fn run() {
     let input = puzzle_input::read_all_lines("./input/2019-d03-input1.txt");
-    let path1 = compute_path(&input[0]);
-    let path2 = compute_path(&input[1]);
+    let wires: Vec<Wire> = input
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| compute_path(line))
+        .collect();
 
-    let nearest = nearest_intersection(&path1, &path2);
+    let nearest = nearest_intersection(&wires);
     println!("** Part 1 Final: {0}", nearest);
 
-    let shortest = shortest_path(&path1, &path2);
+    let shortest = shortest_path(&wires);
     println!("** Part 2 Final: {0}", shortest);
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Point {
-    x: i32,
-    y: i32,
+// A straight run of wire, and the number of steps it took to get to its start
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Segment {
+    start: Point2,
+    end: Point2,
     steps: i32,
 }
-impl hash::Hash for Point {
-    fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        self.x.hash(state);
-        self.y.hash(state);
+
+impl Segment {
+    fn is_horizontal(&self) -> bool {
+        self.start.y() == self.end.y()
     }
-}
-impl cmp::PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
+
+    // (min, max) along the segment
+    fn span(&self) -> (i32, i32) {
+        let (a, b) = if self.is_horizontal() {
+            (self.start.x(), self.end.x())
+        } else {
+            (self.start.y(), self.end.y())
+        };
+        (a.min(b), a.max(b))
     }
-}
-impl cmp::Eq for Point {}
 
-fn insert(point: Point, path: &mut HashSet<Point>) {
-    if !path.insert(point) {
-        println!(
-            "Collision on {:?}. Original is {:?}",
-            point,
-            path.get(&point)
-        );
+    fn steps_to(&self, point: Point2) -> i32 {
+        self.steps + self.start.manhattan(&point)
     }
 }
 
-fn next(last: Point, path: &mut HashSet<Point>, direction: char, n: i32) -> Point {
-    let mut steps = last.steps;
-    let mut pt = last;
-    for m in 1..=n {
-        steps += 1;
-        match direction {
-            'U' => {
-                pt = Point {
-                    x: last.x,
-                    y: last.y + m,
-                    steps,
-                }
-            }
-            'D' => {
-                pt = Point {
-                    x: last.x,
-                    y: last.y - m,
-                    steps,
-                }
-            }
-            'L' => {
-                pt = Point {
-                    x: last.x - m,
-                    y: last.y,
-                    steps,
-                }
-            }
-            'R' => {
-                pt = Point {
-                    x: last.x + m,
-                    y: last.y,
-                    steps,
-                }
-            }
-            _ => {
-                panic!("Bad direction {}", direction);
-            }
-        }
-        insert(pt, path);
-    }
-    pt
+type Wire = Vec<Segment>;
+
+// Where two wires cross, and the fewest steps each wire takes to get there
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Crossing {
+    wires: (usize, usize),
+    point: Point2,
+    steps: (i32, i32),
 }
 
-fn compute_path(input: &str) -> HashSet<Point> {
-    let mut path: HashSet<Point> = HashSet::new();
-    let mut last = Point {
-        x: 0,
-        y: 0,
-        steps: 0,
-    };
+fn compute_path(input: &str) -> Wire {
+    let mut wire = Wire::new();
+    let mut pt = Point2::ORIGIN;
+    let mut steps = 0;
 
     for elem in input.split(',') {
         let direction = elem.chars().next().unwrap();
-        let n = elem
-            .trim_start_matches(|c| c == 'R' || c == 'L' || c == 'U' || c == 'D')
-            .parse::<i32>()
-            .unwrap();
-        last = next(last, &mut path, direction, n);
+        let offset = match Direction::try_from(direction) {
+            Ok(d) => d.offset(),
+            Err(e) => panic!("Bad direction {}", e),
+        };
+        let n = elem[direction.len_utf8()..].parse::<i32>().unwrap();
+        let end = pt + offset * n;
+        wire.push(Segment {
+            start: pt,
+            end,
+            steps,
+        });
+        pt = end;
+        steps += n;
     }
-    println!(
-        "Final path has {:?} elements, last is {:?}",
-        path.len(),
-        last
+    info!(
+        "Final path has {:?} segments and {:?} steps, ends at {:?}",
+        wire.len(),
+        steps,
+        pt
     );
-    path
+    wire
 }
 
-fn nearest_intersection(path1: &HashSet<Point>, path2: &HashSet<Point>) -> i32 {
-    let mut nearest = std::i32::MAX;
-    for point in path1.intersection(path2) {
-        let md = point.x.abs() + point.y.abs();
-        println!("{:?} -> {:?}", point, md);
-        if md < nearest {
-            nearest = md;
+// Every place that two different wires cross (other than where they start).
+// Horizontal and vertical segments are found by sweeping from left to right,
+// segments running along the same line are compared with each other.
+fn crossings(wires: &[Wire]) -> Vec<Crossing> {
+    // (wire, segment) for each segment, grouped by orientation
+    let mut horizontal = Vec::new();
+    let mut vertical = Vec::new();
+    for (w, wire) in wires.iter().enumerate() {
+        for s in wire {
+            if s.is_horizontal() {
+                horizontal.push((w, *s));
+            } else {
+                vertical.push((w, *s));
+            }
         }
     }
-    nearest
-}
 
-fn shortest_path(path1: &HashSet<Point>, path2: &HashSet<Point>) -> i32 {
-    let mut shortest = std::i32::MAX;
+    // fewest steps for each wire to reach each crossing
+    let mut found: HashMap<(usize, usize, Point2), (i32, i32)> = HashMap::new();
+    let mut cross = |a: (usize, Segment), b: (usize, Segment), point: Point2| {
+        if a.0 == b.0 || point == Point2::ORIGIN {
+            return;
+        }
+        let (a, b) = if a.0 < b.0 { (a, b) } else { (b, a) };
+        let steps = (a.1.steps_to(point), b.1.steps_to(point));
+        let best = found.entry((a.0, b.0, point)).or_insert(steps);
+        *best = (best.0.min(steps.0), best.1.min(steps.1));
+    };
 
-    for x in path1.intersection(path2) {
-        let point1 = path1.get(x).unwrap();
-        let point2 = path2.get(x).unwrap();
-        let sum = point1.steps + point2.steps;
-        println!("{:?} <-> {:?} ==> {:?}", point1, point2, sum);
-        if sum < shortest {
-            shortest = sum;
+    // Sweep: horizontal segments are active between their ends,
+    // vertical segments look for active ones within their span.
+    // At the same x, segments start before and end after the vertical ones.
+    let mut events: Vec<(i32, u8, usize)> = Vec::new();
+    for (i, (_, s)) in horizontal.iter().enumerate() {
+        let (x0, x1) = s.span();
+        events.push((x0, 0, i));
+        events.push((x1, 2, i));
+    }
+    for (i, (_, s)) in vertical.iter().enumerate() {
+        events.push((s.start.x(), 1, i));
+    }
+    events.sort_unstable();
+
+    let mut active: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
+    for (x, kind, i) in events {
+        match kind {
+            0 => active.entry(horizontal[i].1.start.y()).or_default().push(i),
+            2 => {
+                let y = horizontal[i].1.start.y();
+                let row = active.get_mut(&y).unwrap();
+                row.retain(|h| *h != i);
+                if row.is_empty() {
+                    active.remove(&y);
+                }
+            }
+            _ => {
+                let (y0, y1) = vertical[i].1.span();
+                for (y, row) in active.range(y0..=y1) {
+                    for h in row {
+                        cross(horizontal[*h], vertical[i], Point2::new(x, *y));
+                    }
+                }
+            }
+        }
+    }
+
+    // Segments on the same line cross wherever they overlap
+    for (segments, horizontal) in [(&horizontal, true), (&vertical, false)] {
+        let mut lines: HashMap<i32, Vec<(usize, Segment)>> = HashMap::new();
+        for (w, s) in segments.iter() {
+            let line = if horizontal { s.start.y() } else { s.start.x() };
+            lines.entry(line).or_default().push((*w, *s));
+        }
+        for (line, on_line) in lines {
+            for (i, a) in on_line.iter().enumerate() {
+                for b in on_line.iter().skip(i + 1) {
+                    let (a0, a1) = a.1.span();
+                    let (b0, b1) = b.1.span();
+                    for v in a0.max(b0)..=a1.min(b1) {
+                        let point = if horizontal {
+                            Point2::new(v, line)
+                        } else {
+                            Point2::new(line, v)
+                        };
+                        cross(*a, *b, point);
+                    }
+                }
+            }
         }
     }
-    shortest
+
+    let mut result: Vec<Crossing> = found
+        .into_iter()
+        .map(|((a, b, point), steps)| Crossing {
+            wires: (a, b),
+            point,
+            steps,
+        })
+        .collect();
+    result.sort_unstable_by_key(|c| (c.wires, c.point));
+    debug!("{:?}", result);
+    result
+}
+
+fn nearest_intersection(wires: &[Wire]) -> i32 {
+    crossings(wires)
+        .iter()
+        .map(|c| c.point.manhattan(&Point2::ORIGIN))
+        .min()
+        .unwrap_or(i32::MAX)
+}
+
+fn shortest_path(wires: &[Wire]) -> i32 {
+    crossings(wires)
+        .iter()
+        .map(|c| c.steps.0 + c.steps.1)
+        .min()
+        .unwrap_or(i32::MAX)
 }
 
 #[cfg(test)]
@@ -146,26 +215,59 @@ mod tests {
     fn test_compute_md_1() {
         let path1 = compute_path("R75,D30,R83,U83,L12,D49,R71,U7,L72");
         let path2 = compute_path("U62,R66,U55,R34,D71,R55,D58,R83");
-        assert_eq!(nearest_intersection(&path1, &path2), 159);
+        assert_eq!(nearest_intersection(&[path1, path2]), 159);
     }
     #[test]
     fn test_compute_md_2() {
         let path3 = compute_path("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51");
         let path4 = compute_path("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7");
-        assert_eq!(nearest_intersection(&path3, &path4), 135);
+        assert_eq!(nearest_intersection(&[path3, path4]), 135);
     }
 
     #[test]
     fn test_compute_combined_path_1() {
         let path1 = compute_path("R75,D30,R83,U83,L12,D49,R71,U7,L72");
         let path2 = compute_path("U62,R66,U55,R34,D71,R55,D58,R83");
-        assert_eq!(shortest_path(&path1, &path2), 610);
+        assert_eq!(shortest_path(&[path1, path2]), 610);
     }
 
     #[test]
     fn test_compute_combined_path_2() {
         let path3 = compute_path("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51");
         let path4 = compute_path("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7");
-        assert_eq!(shortest_path(&path3, &path4), 410);
+        assert_eq!(shortest_path(&[path3, path4]), 410);
+    }
+
+    #[test]
+    fn test_crossings() {
+        let wires = [
+            compute_path("R8,U5,L5,D3"),
+            compute_path("U7,R6,D4,L4"),
+            // runs along part of the first wire, and back over itself
+            compute_path("R2,U5,R5,L5"),
+        ];
+        let found = crossings(&wires);
+        let between = |a, b| -> Vec<(Point2, (i32, i32))> {
+            found
+                .iter()
+                .filter(|c| c.wires == (a, b))
+                .map(|c| (c.point, c.steps))
+                .collect()
+        };
+        assert_eq!(
+            between(0, 1),
+            vec![(Point2::new(3, 3), (20, 20)), (Point2::new(6, 5), (15, 15))]
+        );
+        assert_eq!(nearest_intersection(&wires[..2]), 6);
+        assert_eq!(shortest_path(&wires[..2]), 30);
+
+        // overlapping from (1, 0) to (2, 0), and from (3, 5) to (7, 5):
+        // the third wire passes (3, 5) twice, the first time after 8 steps
+        let overlap = between(0, 2);
+        assert_eq!(overlap.len(), 2 + 5);
+        assert!(overlap.contains(&(Point2::new(3, 5), (18, 8))));
+        assert!(overlap.contains(&(Point2::new(7, 5), (14, 12))));
+        assert!(overlap.contains(&(Point2::new(2, 0), (2, 2))));
+        assert!(!between(1, 2).is_empty());
     }
 }
@@ -1,21 +1,132 @@
+use crate::memo::Memo;
+use crate::puzzle_input;
+use crate::scan;
+
 pub fn run() {
-    let mut n = 0;
-    for input in 353096..=843212 {
-        if is_valid(input) {
-            n += 1;
+    // --param 353096-843212 wins over the input file
+    let input = match puzzle_input::param() {
+        Some(param) => param.to_string(),
+        None => puzzle_input::read_string("./input/2019-d04-input1.txt"),
+    };
+    let (lo, hi) =
+        scan!(input.trim(), "{}-{}", u64, u64).unwrap_or_else(|e| panic!("What is this? {}", e));
+
+    println!("** Part 1 Final: {0}", count(lo, hi, Rule::AtLeast(2)));
+    println!("** Part 2 Final: {0}", count(lo, hi, Rule::Exactly(2)));
+}
+
+// Digits never decrease, and some digit repeats in a run of at least (part 1),
+// or exactly (part 2), k digits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rule {
+    AtLeast(usize),
+    Exactly(usize),
+}
+
+impl Rule {
+    // Does a finished run of this length satisfy the rule?
+    fn closes(&self, run: usize) -> bool {
+        match *self {
+            Rule::AtLeast(k) => run >= k,
+            Rule::Exactly(k) => run == k,
         }
     }
-    println!("** Part 1 Final: {0}", n);
 
-    n = 0;
-    for input in 353096..=843212 {
-        if is_really_valid(input) {
-            n += 1;
+    // Runs longer than k all look the same
+    fn cap(&self) -> usize {
+        match *self {
+            Rule::AtLeast(k) | Rule::Exactly(k) => k + 1,
         }
     }
-    println!("** Part 2 Final: {0}", n);
+
+    // (last digit, length of its run, rule met by an earlier run) after adding a digit.
+    // Before the first digit, the run is empty, and the last digit is 1
+    // (no leading zeros, and digits can't go down from there).
+    fn place(&self, (last, run, met): (u8, usize, bool), d: u8) -> (u8, usize, bool) {
+        if d == last && run > 0 {
+            (d, (run + 1).min(self.cap()), met)
+        } else {
+            (d, 1, met || self.closes(run))
+        }
+    }
+}
+
+// (digits left to place, last digit, run, met)
+type State = (usize, u8, usize, bool);
+
+// Counting digit by digit instead of trying each number: the digits that can
+// follow only depend on the last digit and its run, so those counts are shared.
+struct Counter {
+    rule: Rule,
+    memo: Memo<State, u64>,
 }
 
+impl Counter {
+    fn new(rule: Rule) -> Counter {
+        Counter {
+            rule,
+            memo: Memo::new(),
+        }
+    }
+
+    // Ways to finish from this state, with nothing to stay below
+    fn free(&mut self, state: State) -> u64 {
+        let rule = self.rule;
+        self.memo.get(state, &|&(left, last, run, met), free| {
+            if left == 0 {
+                return (met || rule.closes(run)) as u64;
+            }
+            (last..=9)
+                .map(|d| {
+                    let (last, run, met) = rule.place((last, run, met), d);
+                    free((left - 1, last, run, met))
+                })
+                .sum()
+        })
+    }
+
+    // Valid passwords in 1..=n
+    fn up_to(&mut self, n: u64) -> u64 {
+        let digits: Vec<u8> = n.to_string().bytes().map(|b| b - b'0').collect();
+        let len = digits.len();
+
+        // every shorter number
+        let mut total: u64 = (1..len).map(|l| self.free((l, 1, 0, false))).sum();
+
+        // numbers as long as n: follow its digits, branching off with a smaller one
+        let mut state = (1, 0, false);
+        for (i, &top) in digits.iter().enumerate() {
+            for d in state.0..top {
+                let (last, run, met) = self.rule.place(state, d);
+                total += self.free((len - i - 1, last, run, met));
+            }
+            if top < state.0 {
+                return total;
+            }
+            state = self.rule.place(state, top);
+        }
+
+        // and n itself
+        if state.2 || self.rule.closes(state.1) {
+            total += 1;
+        }
+        total
+    }
+}
+
+// Valid passwords in lo..=hi
+fn count(lo: u64, hi: u64, rule: Rule) -> u64 {
+    if hi < lo {
+        return 0;
+    }
+    let mut counter = Counter::new(rule);
+    counter.up_to(hi) - counter.up_to(lo.saturating_sub(1))
+}
+
+// Checking one password at a time: too slow for long ranges,
+// kept to check the counts against
+
+#[allow(dead_code)]
 fn to_digits(input: i32) -> Vec<i32> {
     let mut x = input;
     let mut result: Vec<i32> = Vec::new();
@@ -31,6 +142,7 @@ fn to_digits(input: i32) -> Vec<i32> {
     result
 }
 
+#[allow(dead_code)]
 fn is_valid(password: i32) -> bool {
     let input = to_digits(password);
 
@@ -52,6 +164,7 @@ fn is_valid(password: i32) -> bool {
     input.len() == 6 && adjacent && !decrease
 }
 
+#[allow(dead_code)]
 fn is_really_valid(password: i32) -> bool {
     let input = to_digits(password);
 
@@ -108,4 +221,68 @@ mod tests {
         assert_eq!(is_really_valid(112233), true);
         assert_eq!(is_really_valid(123444), false);
     }
+
+    // any length, any rule
+    fn matches(password: u64, rule: Rule) -> bool {
+        let digits = password.to_string();
+        let digits = digits.as_bytes();
+        if digits.windows(2).any(|w| w[0] > w[1]) {
+            return false;
+        }
+        digits
+            .chunk_by(|a, b| a == b)
+            .any(|run| rule.closes(run.len()))
+    }
+
+    fn brute_force(lo: u64, hi: u64, rule: Rule) -> u64 {
+        (lo..=hi).filter(|&n| matches(n, rule)).count() as u64
+    }
+
+    #[test]
+    fn test_count() {
+        let valid = (353096..=843212).filter(|&n| is_valid(n)).count() as u64;
+        let really_valid = (353096..=843212).filter(|&n| is_really_valid(n)).count() as u64;
+        assert_eq!(count(353096, 843212, Rule::AtLeast(2)), valid);
+        assert_eq!(count(353096, 843212, Rule::Exactly(2)), really_valid);
+
+        for &(lo, hi) in &[
+            (1, 1),
+            (0, 9999),
+            (11, 11),
+            (100, 12345),
+            (5, 4),
+            (99999, 111112),
+        ] {
+            for &rule in &[
+                Rule::AtLeast(2),
+                Rule::Exactly(2),
+                Rule::AtLeast(3),
+                Rule::Exactly(3),
+            ] {
+                assert_eq!(
+                    count(lo, hi, rule),
+                    brute_force(lo, hi, rule),
+                    "{:?} {}-{}",
+                    rule,
+                    lo,
+                    hi
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_long() {
+        // every non-decreasing 12 digit number: 12 digits from 1..=9, C(20, 8)
+        assert_eq!(
+            count(100_000_000_000, 999_999_999_999, Rule::AtLeast(1)),
+            125970
+        );
+        // only 123456789 has no repeats, and it is too short
+        assert_eq!(
+            count(100_000_000_000, 999_999_999_999, Rule::AtLeast(2)),
+            125970
+        );
+        assert!(count(1, u64::MAX, Rule::Exactly(2)) < count(1, u64::MAX, Rule::AtLeast(2)));
+    }
 }
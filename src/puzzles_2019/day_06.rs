@@ -1,37 +1,262 @@
+use crate::debug;
 use crate::puzzle_input;
-use petgraph::algo::astar;
-use petgraph::algo::dijkstra;
-use petgraph::graphmap::UnGraphMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 
 pub fn run() {
     let input = puzzle_input::read_string("./input/2019-d06-input1.txt");
-    let g = parse_input(&input);
-    println!("** Part 1 Final: {:?}", chksum_orbits(&g));
-    println!("** Part 2 Final: {:?}", count_transfers(&g));
+    let map = OrbitMap::parse(&input).unwrap_or_else(|e| panic!("What is this? {}", e));
+    println!("** Part 1 Final: {:?}", map.checksum());
+
+    let path = map.path("YOU", "SAN").unwrap();
+    debug!(
+        "YOU ({:?} orbits) and SAN ({:?} orbits) meet at {:?}: {}",
+        map.depth("YOU").unwrap(),
+        map.depth("SAN").unwrap(),
+        map.lca("YOU", "SAN").unwrap(),
+        path.join(" -> ")
+    );
+    println!(
+        "** Part 2 Final: {:?}",
+        map.transfers("YOU", "SAN").unwrap()
+    );
 }
 
-fn parse_input(input_ref: &str) -> UnGraphMap<&str, i32> {
-    let mut graph = UnGraphMap::new();
-    graph.add_node("COM");
+// Everything orbits COM, directly or indirectly
+const ROOT: &str = "COM";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum OrbitError {
+    // Line number, and what was found there
+    Malformed(usize, String),
+    // A body can only orbit one other: (body, first parent, second parent)
+    MultipleParents(String, String, String),
+    // Bodies that orbit each other, each orbited by the next
+    Cycle(Vec<String>),
+    // Bodies with no path to COM
+    Unreachable(Vec<String>),
+    // A body that isn't in the map
+    Unknown(String),
+}
 
-    for entry in input_ref.split_whitespace() {
-        let v: Vec<&str> = entry.split(')').collect();
-        graph.add_edge(v[0], v[1], 1);
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrbitError::Malformed(n, s) => write!(f, "Expected A)B at line {}: {:?}", n, s),
+            OrbitError::MultipleParents(body, a, b) => {
+                write!(f, "{} orbits both {} and {}", body, a, b)
+            }
+            OrbitError::Cycle(bodies) => {
+                write!(
+                    f,
+                    "Orbits form a cycle: {} -> {}",
+                    bodies.join(" -> "),
+                    bodies[0]
+                )
+            }
+            OrbitError::Unreachable(bodies) => {
+                write!(f, "Not in orbit around {}: {}", ROOT, bodies.join(", "))
+            }
+            OrbitError::Unknown(body) => write!(f, "No such body: {}", body),
+        }
     }
-    graph
 }
 
-fn chksum_orbits(graph: &UnGraphMap<&str, i32>) -> i32 {
-    let res = dijkstra(&graph, "COM", None, |_| 1);
-    let mut i = 0;
-    res.iter().for_each(|(_, v)| i += v);
-    i
+// The orbits as a tree rooted at COM: each body knows what it orbits,
+// and how many orbits (direct and indirect) it has
+#[derive(Clone, Debug)]
+struct OrbitMap {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    parent: Vec<Option<usize>>,
+    depth: Vec<usize>,
 }
 
-fn count_transfers(graph: &UnGraphMap<&str, i32>) -> i32 {
-    let path = astar(&graph, "YOU", |finish| finish == "SAN", |_| 1, |_| 0);
-    println!("{:?}", path);
-    path.unwrap().0 - 2
+impl OrbitMap {
+    fn parse(input: &str) -> Result<OrbitMap, OrbitError> {
+        let mut map = OrbitMap {
+            names: Vec::new(),
+            index: HashMap::new(),
+            parent: Vec::new(),
+            depth: Vec::new(),
+        };
+        map.id(ROOT);
+
+        for (n, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (center, body) = match line.split_once(')') {
+                Some((a, b)) if !a.is_empty() && !b.is_empty() && !b.contains(')') => (a, b),
+                _ => return Err(OrbitError::Malformed(n + 1, line.to_string())),
+            };
+            let center = map.id(center);
+            let body = map.id(body);
+            if let Some(other) = map.parent[body] {
+                return Err(OrbitError::MultipleParents(
+                    map.names[body].clone(),
+                    map.names[other].clone(),
+                    map.names[center].clone(),
+                ));
+            }
+            map.parent[body] = Some(center);
+        }
+
+        map.measure()?;
+        Ok(map)
+    }
+
+    fn id(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.index.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.index.insert(name.to_string(), id);
+        self.parent.push(None);
+        id
+    }
+
+    // Depth of every body, outward from COM. Bodies that can't be reached
+    // are either part of (or orbiting) a cycle, or orbit something else
+    fn measure(&mut self) -> Result<(), OrbitError> {
+        let n = self.names.len();
+        let mut children = vec![Vec::new(); n];
+        for (body, parent) in self.parent.iter().enumerate() {
+            if let Some(p) = parent {
+                children[*p].push(body);
+            }
+        }
+
+        // COM orbits nothing; if it does, it is part of a cycle
+        // (or orbits something that can't be reached)
+        if let Some(cycle) = self.parent[0].and_then(|_| self.cycle_from(0)) {
+            return Err(OrbitError::Cycle(cycle));
+        }
+
+        let mut depth = vec![None; n];
+        depth[0] = Some(0);
+        let mut queue = VecDeque::from(vec![0]);
+        while let Some(body) = queue.pop_front() {
+            let d = depth[body].map(|d| d + 1);
+            for &child in children[body].iter() {
+                if depth[child].is_none() {
+                    depth[child] = d;
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        let mut unreachable: Vec<usize> = (0..n).filter(|b| depth[*b].is_none()).collect();
+        for &body in unreachable.iter() {
+            if let Some(cycle) = self.cycle_from(body) {
+                return Err(OrbitError::Cycle(cycle));
+            }
+        }
+        if !unreachable.is_empty() {
+            unreachable.sort_by_key(|b| &self.names[*b]);
+            return Err(OrbitError::Unreachable(
+                unreachable.iter().map(|b| self.names[*b].clone()).collect(),
+            ));
+        }
+
+        self.depth = depth.into_iter().map(|d| d.unwrap()).collect();
+        Ok(())
+    }
+
+    // Follow parents from body: the cycle it leads into, if any
+    fn cycle_from(&self, body: usize) -> Option<Vec<String>> {
+        let mut seen = HashMap::new();
+        let mut chain = Vec::new();
+        let mut current = Some(body);
+        while let Some(b) = current {
+            if let Some(&start) = seen.get(&b) {
+                // in the order of the input: each one orbited by the next
+                let cycle: Vec<usize> = chain[start..].iter().rev().copied().collect();
+                // start with the smallest name, so the report is always the same
+                let first = (0..cycle.len())
+                    .min_by_key(|i| &self.names[cycle[*i]])
+                    .unwrap();
+                return Some(
+                    cycle[first..]
+                        .iter()
+                        .chain(cycle[..first].iter())
+                        .map(|b| self.names[*b].clone())
+                        .collect(),
+                );
+            }
+            seen.insert(b, chain.len());
+            chain.push(b);
+            current = self.parent[b];
+        }
+        None
+    }
+
+    fn lookup(&self, body: &str) -> Result<usize, OrbitError> {
+        self.index
+            .get(body)
+            .copied()
+            .ok_or_else(|| OrbitError::Unknown(body.to_string()))
+    }
+
+    // Direct and indirect orbits of one body
+    fn depth(&self, body: &str) -> Result<usize, OrbitError> {
+        Ok(self.depth[self.lookup(body)?])
+    }
+
+    // Direct and indirect orbits of every body
+    fn checksum(&self) -> usize {
+        self.depth.iter().sum()
+    }
+
+    fn lca_id(&self, a: usize, b: usize) -> usize {
+        let (mut a, mut b) = (a, b);
+        while self.depth[a] > self.depth[b] {
+            a = self.parent[a].unwrap();
+        }
+        while self.depth[b] > self.depth[a] {
+            b = self.parent[b].unwrap();
+        }
+        while a != b {
+            a = self.parent[a].unwrap();
+            b = self.parent[b].unwrap();
+        }
+        a
+    }
+
+    // The nearest body that both a and b orbit (or are)
+    fn lca(&self, a: &str, b: &str) -> Result<&str, OrbitError> {
+        let common = self.lca_id(self.lookup(a)?, self.lookup(b)?);
+        Ok(&self.names[common])
+    }
+
+    // Bodies from a to b, both included: in toward the common ancestor, then out again
+    fn path(&self, a: &str, b: &str) -> Result<Vec<&str>, OrbitError> {
+        let (a, b) = (self.lookup(a)?, self.lookup(b)?);
+        let common = self.lca_id(a, b);
+
+        let climb = |mut body: usize| {
+            let mut steps = vec![body];
+            while body != common {
+                body = self.parent[body].unwrap();
+                steps.push(body);
+            }
+            steps
+        };
+        let mut path = climb(a);
+        let mut down = climb(b);
+        down.pop();
+        path.extend(down.into_iter().rev());
+
+        Ok(path.iter().map(|b| self.names[*b].as_str()).collect())
+    }
+
+    // Orbital transfers to move from what a orbits to what b orbits
+    fn transfers(&self, a: &str, b: &str) -> Result<usize, OrbitError> {
+        let steps = self.path(a, b)?.len() - 1;
+        Ok(steps.saturating_sub(2))
+    }
 }
 
 #[cfg(test)]
@@ -52,10 +277,13 @@ mod tests {
         J)K
         K)L";
 
-        let g = parse_input(input);
-        println!("{:?}", g);
+        let map = OrbitMap::parse(input).unwrap();
+        println!("{:?}", map);
 
-        assert_eq!(chksum_orbits(&g), 42);
+        assert_eq!(map.checksum(), 42);
+        assert_eq!(map.depth("D"), Ok(3));
+        assert_eq!(map.depth("L"), Ok(7));
+        assert_eq!(map.depth("COM"), Ok(0));
     }
 
     #[test]
@@ -74,9 +302,46 @@ mod tests {
         K)YOU
         I)SAN";
 
-        let g = parse_input(input);
-        println!("{:?}", g);
+        let map = OrbitMap::parse(input).unwrap();
+        println!("{:?}", map);
+
+        assert_eq!(map.transfers("YOU", "SAN"), Ok(4));
+        assert_eq!(map.lca("YOU", "SAN"), Ok("D"));
+        assert_eq!(map.lca("H", "L"), Ok("B"));
+        assert_eq!(map.lca("K", "L"), Ok("K"));
+        assert_eq!(
+            map.path("YOU", "SAN"),
+            Ok(vec!["YOU", "K", "J", "E", "D", "I", "SAN"])
+        );
+        assert_eq!(map.path("L", "J"), Ok(vec!["L", "K", "J"]));
+        assert_eq!(map.path("F", "F"), Ok(vec!["F"]));
+        assert_eq!(
+            map.lca("YOU", "PLUTO"),
+            Err(OrbitError::Unknown("PLUTO".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_orbits() {
+        let err = OrbitMap::parse("COM)B\nB-C").unwrap_err();
+        assert_eq!(err, OrbitError::Malformed(2, "B-C".to_string()));
+
+        let err = OrbitMap::parse("COM)B\nCOM)C\nB)D\nC)D").unwrap_err();
+        assert_eq!(err.to_string(), "D orbits both B and C");
+
+        let err = OrbitMap::parse("COM)B\nX)Y\nY)Z\nZ)X\nZ)W").unwrap_err();
+        assert_eq!(
+            err,
+            OrbitError::Cycle(vec!["X".to_string(), "Y".to_string(), "Z".to_string()])
+        );
+        assert_eq!(err.to_string(), "Orbits form a cycle: X -> Y -> Z -> X");
+
+        let err = OrbitMap::parse("COM)A\nA)COM").unwrap_err();
+        assert_eq!(err.to_string(), "Orbits form a cycle: A -> COM -> A");
+        let err = OrbitMap::parse("COM)A\nX)COM").unwrap_err();
+        assert_eq!(err.to_string(), "Not in orbit around COM: X");
 
-        assert_eq!(count_transfers(&g), 4);
+        let err = OrbitMap::parse("COM)B\nSUN)EARTH\nEARTH)MOON").unwrap_err();
+        assert_eq!(err.to_string(), "Not in orbit around COM: EARTH, MOON, SUN");
     }
 }
@@ -1,5 +1,7 @@
+use super::analyze;
 use super::compute;
 use crate::puzzle_input;
+use crate::{debug, info, trace};
 
 pub fn run() {
     let input = puzzle_input::read_string("./input/2019-d09-input1.txt");
@@ -9,6 +11,15 @@ pub fn run() {
         .map(|x| x.trim().parse::<i64>().unwrap())
         .collect();
 
+    let analysis = analyze::analyze(&codes);
+    info!("BOOST program: {}", analysis.to_string().trim_end());
+    debug!(
+        "Reachable only through indirect jumps: {:?}",
+        analysis.unreachable_blocks()
+    );
+    // -vvv prints the control flow graph, ready for Graphviz
+    trace!("Control flow graph:\n{}", analysis.to_dot());
+
     let mut io = compute::DefaultProgramIO::new(vec![1]); // test mode
     compute::run(&mut codes, &mut io);
     println!("** Part 1 Final: {:?}", io);
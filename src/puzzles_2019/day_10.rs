@@ -1,7 +1,9 @@
+use crate::math;
+use crate::point::Point2;
 use crate::puzzle_input;
-use std::collections::HashSet;
-use std::f64::consts::{FRAC_PI_2, PI};
-use std::ops;
+use crate::{info, trace};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 // I got stuck on this one.
 // https://github.com/prscoelho/aoc2019/blob/master/src/aoc10/mod.rs
@@ -10,99 +12,87 @@ pub fn run() {
     let input = parse_input(&puzzle_input::read_all_lines("./input/2019-d10-input1.txt"));
 
     println!("** Part 1 Final: {:?}", find_most_asteroids(&input));
-    let blast_seq = blast_asteroids(&input, &Point { x: 20, y: 19 });
+    let blast_seq = blast_asteroids(&input, &Point2::new(20, 19));
     println!("** Part 2 Final: {:?}", get_happy(&blast_seq[199]));
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-impl Point {
-    fn angle(&self) -> f64 {
-        // y.atan2(x)
-        let result = (self.y as f64).atan2(self.x as f64);
-        if result < 0.0 {
-            result + 2.0 * PI
+// The direction from one asteroid to another, reduced by the gcd so that
+// everything on the same line of sight has the same bearing. Kept in screen
+// coordinates (y grows down), and ordered clockwise starting from straight up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Bearing(i32, i32);
+
+impl Bearing {
+    fn new(p: &Point2, src: &Point2) -> Bearing {
+        let diff = *p - *src;
+        let g = math::gcd(diff.x() as i64, diff.y() as i64) as i32;
+        Bearing(diff.x() / g, diff.y() / g)
+    }
+
+    // up to (not including) straight down, or the rest of the way round
+    fn half(&self) -> u8 {
+        if self.0 > 0 || (self.0 == 0 && self.1 < 0) {
+            0
         } else {
-            result
+            1
         }
     }
+}
 
-    fn mag(&self) -> i32 {
-        self.x * self.x + self.y * self.y
+impl Ord for Bearing {
+    fn cmp(&self, other: &Bearing) -> Ordering {
+        // within a half turn, other is clockwise of self when the cross product is positive
+        let cross = self.0 as i64 * other.1 as i64 - self.1 as i64 * other.0 as i64;
+        self.half().cmp(&other.half()).then(0.cmp(&cross))
     }
 }
-impl ops::Sub for Point {
-    type Output = Point;
-
-    fn sub(self, rhs: Point) -> Self::Output {
-        // because 0,0 is top left instead of bottom left,
-        // reverse the y values. seems hacky: any better way to do this?
-        Point {
-            x: self.x - rhs.x,
-            y: rhs.y - self.y,
-        }
+
+impl PartialOrd for Bearing {
+    fn partial_cmp(&self, other: &Bearing) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-fn find_asteroids(data: &[Point], src: &Point) -> usize {
-    let mut set = HashSet::new();
+fn mag(p: &Point2, src: &Point2) -> i32 {
+    p.manhattan(src)
+}
+
+fn find_asteroids(data: &[Point2], src: &Point2) -> usize {
     data.iter()
         .filter(|p| *p != src)
-        .map(|p| {
-            let diff = *p - *src;
-            diff.angle()
-        })
-        .filter(|x| set.insert(x.to_string()))
-        .count()
+        .map(|p| Bearing::new(p, src))
+        .collect::<HashSet<_>>()
+        .len()
 }
 
-fn blast_asteroids(data: &[Point], src: &Point) -> Vec<Point> {
-    let mut list: Vec<(f64, i32, Point)> = data
-        .iter()
-        .filter(|p| *p != src)
-        .map(|p| {
-            let diff = *p - *src;
-            (diff.angle(), diff.mag(), *p)
-        })
-        .collect();
-
-    list.sort_by(|a, b| {
-        // sort by reverse angle (clockwise), and then magnitude
-        a.0.partial_cmp(&b.0).unwrap().reverse().then(a.1.cmp(&b.1))
-    });
-
-    let n = list.len();
-    let mut blast_order = Vec::with_capacity(n);
-
-    for (idx, v) in list.iter().enumerate() {
-        if v.0 <= FRAC_PI_2 {
-            blast_order.push(list.remove(idx));
-            break;
-        }
+fn blast_asteroids(data: &[Point2], src: &Point2) -> Vec<Point2> {
+    // one bucket per line of sight, farthest asteroid first (taken from the end)
+    let mut buckets: HashMap<Bearing, Vec<Point2>> = HashMap::new();
+    for p in data.iter().filter(|p| *p != src) {
+        buckets.entry(Bearing::new(p, src)).or_default().push(*p);
     }
-    if blast_order.last().is_none() {
-        blast_order.push(list.remove(0));
+    let mut sweep: Vec<(Bearing, Vec<Point2>)> = buckets.into_iter().collect();
+    sweep.sort_by_key(|(bearing, _)| *bearing);
+    for (_, bucket) in sweep.iter_mut() {
+        bucket.sort_by_key(|p| -mag(p, src));
     }
 
-    'outer: for _ in 1..n {
-        for (idx, v) in list.iter().enumerate() {
-            if v.0 < blast_order.last().unwrap().0 {
-                blast_order.push(list.remove(idx));
-                continue 'outer;
-            }
+    // go round, taking the nearest asteroid from each line of sight,
+    // until every bucket is empty
+    let mut sweep: VecDeque<Vec<Point2>> = sweep.into_iter().map(|(_, b)| b).collect();
+    let mut blast_order = Vec::with_capacity(data.len());
+    while let Some(mut bucket) = sweep.pop_front() {
+        blast_order.push(bucket.pop().unwrap());
+        if !bucket.is_empty() {
+            sweep.push_back(bucket);
         }
-        // reached end of list without removing, remove first element
-        blast_order.push(list.remove(0));
-        println!("{:?} ", list.len());
     }
+    trace!("{:?} blasted", blast_order.len());
 
-    blast_order.iter().map(|x| x.2).collect()
+    blast_order
 }
 
-fn find_most_asteroids(data: &[Point]) -> i32 {
+fn find_most_asteroids(data: &[Point2]) -> i32 {
     let mut max = 0;
     let mut p = &data[0];
     for point in data {
@@ -112,24 +102,21 @@ fn find_most_asteroids(data: &[Point]) -> i32 {
             p = point;
         }
     }
-    println!("{:?} can see the most ({:?}) asteroids", p, max);
+    info!("{:?} can see the most ({:?}) asteroids", p, max);
     max as i32
 }
 
-fn get_happy(p: &Point) -> i32 {
-    p.x as i32 * 100 + p.y as i32
+fn get_happy(p: &Point2) -> i32 {
+    p.x() * 100 + p.y()
 }
 
-fn parse_input(input: &[String]) -> Vec<Point> {
-    let mut data: Vec<Point> = Vec::new();
+fn parse_input(input: &[String]) -> Vec<Point2> {
+    let mut data: Vec<Point2> = Vec::new();
 
     for (y, row) in input.iter().enumerate() {
         for (x, ch) in row.chars().enumerate() {
             if ch == '#' {
-                data.push(Point {
-                    x: x as i32,
-                    y: y as i32,
-                });
+                data.push(Point2::new(x as i32, y as i32));
             }
         }
     }
@@ -153,9 +140,9 @@ mod tests {
             .collect();
 
         let data = parse_input(&input);
-        assert_eq!(find_asteroids(&data, &Point { x: 1, y: 0 }), 7);
-        assert_eq!(find_asteroids(&data, &Point { x: 3, y: 4 }), 8);
-        assert_eq!(find_asteroids(&data, &Point { x: 0, y: 2 }), 6);
+        assert_eq!(find_asteroids(&data, &Point2::new(1, 0)), 7);
+        assert_eq!(find_asteroids(&data, &Point2::new(3, 4)), 8);
+        assert_eq!(find_asteroids(&data, &Point2::new(0, 2)), 6);
 
         assert_eq!(find_most_asteroids(&data), 8);
     }
@@ -249,18 +236,56 @@ mod tests {
         let data = parse_input(&input);
         assert_eq!(find_most_asteroids(&data), 210);
 
-        let blast_seq = blast_asteroids(&data, &Point { x: 11, y: 13 });
-        assert_eq!(&blast_seq[0], &Point { x: 11, y: 12 });
-        assert_eq!(&blast_seq[1], &Point { x: 12, y: 1 });
-        assert_eq!(&blast_seq[2], &Point { x: 12, y: 2 });
-        assert_eq!(&blast_seq[9], &Point { x: 12, y: 8 });
-        assert_eq!(&blast_seq[19], &Point { x: 16, y: 0 });
-        assert_eq!(&blast_seq[49], &Point { x: 16, y: 9 });
-        assert_eq!(&blast_seq[99], &Point { x: 10, y: 16 });
-        assert_eq!(&blast_seq[198], &Point { x: 9, y: 6 });
-        assert_eq!(&blast_seq[199], &Point { x: 8, y: 2 });
-        assert_eq!(&blast_seq[200], &Point { x: 10, y: 9 });
-        assert_eq!(&blast_seq[298], &Point { x: 11, y: 1 });
+        let blast_seq = blast_asteroids(&data, &Point2::new(11, 13));
+        assert_eq!(&blast_seq[0], &Point2::new(11, 12));
+        assert_eq!(&blast_seq[1], &Point2::new(12, 1));
+        assert_eq!(&blast_seq[2], &Point2::new(12, 2));
+        assert_eq!(&blast_seq[9], &Point2::new(12, 8));
+        assert_eq!(&blast_seq[19], &Point2::new(16, 0));
+        assert_eq!(&blast_seq[49], &Point2::new(16, 9));
+        assert_eq!(&blast_seq[99], &Point2::new(10, 16));
+        assert_eq!(&blast_seq[198], &Point2::new(9, 6));
+        assert_eq!(&blast_seq[199], &Point2::new(8, 2));
+        assert_eq!(&blast_seq[200], &Point2::new(10, 9));
+        assert_eq!(&blast_seq[298], &Point2::new(11, 1));
         assert_eq!(get_happy(&blast_seq[199]), 802);
+        assert_eq!(blast_seq.len(), data.len() - 1);
+    }
+
+    #[test]
+    fn test_bearing() {
+        let src = Point2::new(0, 0);
+        assert_eq!(Bearing::new(&Point2::new(4, -6), &src), Bearing(2, -3));
+        assert_eq!(Bearing::new(&Point2::new(0, 7), &src), Bearing(0, 1));
+
+        // clockwise from straight up; x grows right, y grows down
+        let mut bearings = vec![
+            Bearing(-1, -1),
+            Bearing(0, 1),
+            Bearing(1, 0),
+            Bearing(-1, 0),
+            Bearing(1, 1),
+            Bearing(0, -1),
+            Bearing(1, -1),
+            Bearing(-1, 1),
+            Bearing(1_000_000, -999_999),
+            Bearing(999_999, -1_000_000),
+        ];
+        bearings.sort();
+        assert_eq!(
+            bearings,
+            vec![
+                Bearing(0, -1),
+                Bearing(999_999, -1_000_000),
+                Bearing(1, -1),
+                Bearing(1_000_000, -999_999),
+                Bearing(1, 0),
+                Bearing(1, 1),
+                Bearing(0, 1),
+                Bearing(-1, 1),
+                Bearing(-1, 0),
+                Bearing(-1, -1),
+            ]
+        );
     }
 }
@@ -0,0 +1,272 @@
+use super::compute::{self, DefaultProgramIO};
+
+extern crate scoped_threadpool;
+use crate::debug;
+use scoped_threadpool::Pool;
+use std::ops::RangeInclusive;
+use std::sync::Mutex;
+use std::thread;
+
+// Searching for the values to patch into an Intcode program before it runs
+// (like the noun and verb at addresses 1 and 2) so that the final state
+// satisfies a predicate. Values are tried in order, the last address changing
+// fastest, so the first match is always the same one.
+
+// The state of memory, and everything written, after the program halts
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub memory: Vec<i64>,
+    pub output: Vec<i64>,
+}
+
+// Address 0 as constant + sum of coefficient * value, one per patched address
+#[derive(Clone, Debug, PartialEq)]
+pub struct Linear {
+    pub constant: i64,
+    pub coefficients: Vec<i64>,
+}
+
+impl Linear {
+    pub fn eval(&self, values: &[i64]) -> i64 {
+        self.constant
+            + self
+                .coefficients
+                .iter()
+                .zip(values)
+                .map(|(k, x)| k * x)
+                .sum::<i64>()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParamSearch<'a> {
+    program: &'a [i64],
+    params: Vec<(usize, RangeInclusive<i64>)>,
+    input: Vec<i64>,
+}
+
+impl<'a> ParamSearch<'a> {
+    pub fn new(program: &'a [i64]) -> ParamSearch<'a> {
+        ParamSearch {
+            program,
+            params: Vec::new(),
+            input: Vec::new(),
+        }
+    }
+
+    // Patch this address with each value in the range
+    pub fn param(mut self, addr: usize, range: RangeInclusive<i64>) -> ParamSearch<'a> {
+        self.params.push((addr, range));
+        self
+    }
+
+    // Values for the program to read while it runs
+    #[allow(dead_code)]
+    pub fn input(mut self, input: Vec<i64>) -> ParamSearch<'a> {
+        self.input = input;
+        self
+    }
+
+    // Number of combinations to try
+    pub fn len(&self) -> usize {
+        self.params
+            .iter()
+            .map(|(_, r)| (r.end() - r.start() + 1).max(0) as usize)
+            .product()
+    }
+
+    // The nth combination, counting like digits with the last address fastest
+    fn values(&self, mut n: usize) -> Vec<i64> {
+        let mut values = vec![0; self.params.len()];
+        for (i, (_, range)) in self.params.iter().enumerate().rev() {
+            let size = (range.end() - range.start() + 1) as usize;
+            values[i] = range.start() + (n % size) as i64;
+            n /= size;
+        }
+        values
+    }
+
+    // Run once with the given values patched in
+    pub fn run(&self, values: &[i64]) -> Outcome {
+        let mut memory = self.program.to_vec();
+        for ((addr, _), value) in self.params.iter().zip(values) {
+            memory[*addr] = *value;
+        }
+        let mut io = DefaultProgramIO::new(self.input.clone());
+        compute::run(&mut memory, &mut io);
+        Outcome {
+            memory,
+            output: io.outputs().to_vec(),
+        }
+    }
+
+    // Try every combination, spread over all cores;
+    // return the first (in order) that satisfies the predicate
+    pub fn search<P>(&self, predicate: P) -> Option<Vec<i64>>
+    where
+        P: Fn(&Outcome) -> bool + Sync,
+    {
+        let total = self.len();
+        let threads = thread::available_parallelism().map_or(4, |n| n.get());
+        let chunk = (total / threads).max(1);
+
+        // combinations at or past the best match so far are skipped
+        let best: Mutex<Option<usize>> = Mutex::new(None);
+        let mut pool = Pool::new(threads as u32);
+        pool.scoped(|scope| {
+            for start in (0..total).step_by(chunk) {
+                let (best, predicate) = (&best, &predicate);
+                scope.execute(move || {
+                    for n in start..(start + chunk).min(total) {
+                        if best.lock().unwrap().is_some_and(|b| b < n) {
+                            return;
+                        }
+                        if predicate(&self.run(&self.values(n))) {
+                            let mut best = best.lock().unwrap();
+                            if best.is_none_or(|b| n < b) {
+                                *best = Some(n);
+                            }
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        best.into_inner().unwrap().map(|n| self.values(n))
+    }
+
+    // Measure how address 0 changes with each value, and check the guess
+    // at a few other combinations: Some if it looks linear everywhere tried
+    pub fn linear(&self) -> Option<Linear> {
+        if self.len() == 0 {
+            return None;
+        }
+        let low: Vec<i64> = self.params.iter().map(|(_, r)| *r.start()).collect();
+        let high: Vec<i64> = self.params.iter().map(|(_, r)| *r.end()).collect();
+        let at = |values: &[i64]| self.run(values).memory[0];
+
+        let base = at(&low);
+        let mut coefficients = Vec::with_capacity(low.len());
+        for i in 0..low.len() {
+            if high[i] == low[i] {
+                coefficients.push(0);
+                continue;
+            }
+            let mut next = low.clone();
+            next[i] += 1;
+            coefficients.push(at(&next) - base);
+        }
+        let constant = base
+            - coefficients
+                .iter()
+                .zip(&low)
+                .map(|(k, x)| k * x)
+                .sum::<i64>();
+        let linear = Linear {
+            constant,
+            coefficients,
+        };
+
+        let mut probes = vec![high.clone(), self.values(self.len() / 2)];
+        for i in 0..low.len() {
+            let mut probe = low.clone();
+            probe[i] = high[i];
+            probes.push(probe);
+        }
+        if probes.iter().all(|p| at(p) == linear.eval(p)) {
+            Some(linear)
+        } else {
+            None
+        }
+    }
+
+    // Values that leave target at address 0: solved from the linear form when
+    // there is one (and the answer checks out), searched for otherwise
+    pub fn solve(&self, target: i64) -> Option<Vec<i64>> {
+        if let Some(linear) = self.linear() {
+            debug!("Address 0 looks linear: {:?}", linear);
+            let mut values = Vec::with_capacity(self.params.len());
+            if let Some(values) = self.solve_linear(&linear, target - linear.constant, &mut values)
+            {
+                if self.run(&values).memory[0] == target {
+                    return Some(values);
+                }
+            }
+            debug!("No solution from the linear form, searching");
+        }
+        self.search(|o| o.memory[0] == target)
+    }
+
+    // Choose values in order until the last one, which is solved for
+    fn solve_linear(&self, linear: &Linear, rest: i64, values: &mut Vec<i64>) -> Option<Vec<i64>> {
+        let i = values.len();
+        let (k, range) = (linear.coefficients[i], &self.params[i].1);
+        if i == self.params.len() - 1 {
+            let x = match k {
+                0 if rest == 0 => *range.start(),
+                0 => return None,
+                k if rest % k == 0 => rest / k,
+                _ => return None,
+            };
+            if !range.contains(&x) {
+                return None;
+            }
+            let mut found = values.clone();
+            found.push(x);
+            return Some(found);
+        }
+        for x in range.clone() {
+            values.push(x);
+            let found = self.solve_linear(linear, rest - k * x, values);
+            values.pop();
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<i64> {
+        input
+            .split(',')
+            .map(|x| x.trim().parse::<i64>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_linear() {
+        // [0] = 7 * [10] + [11]
+        let codes = parse("1002,10,7,0,1,0,11,0,99,0,0,0");
+        let search = ParamSearch::new(&codes).param(10, 0..=9).param(11, 0..=9);
+        assert_eq!(search.len(), 100);
+        assert_eq!(search.run(&[3, 4]).memory[0], 25);
+        assert_eq!(
+            search.linear(),
+            Some(Linear {
+                constant: 0,
+                coefficients: vec![7, 1]
+            })
+        );
+        // 2 * 7 + 11 is out of range
+        assert_eq!(search.solve(25), Some(vec![3, 4]));
+        assert_eq!(search.solve(500), None);
+        assert_eq!(search.search(|o| o.memory[0] == 25), Some(vec![3, 4]));
+    }
+
+    #[test]
+    fn test_search() {
+        // [0] = [8] * [9], written out: not linear
+        let codes = parse("2,8,9,0,4,0,99,0,0,0");
+        let search = ParamSearch::new(&codes).param(8, 1..=20).param(9, 1..=20);
+        assert_eq!(search.linear(), None);
+        assert_eq!(search.solve(91), Some(vec![7, 13]));
+        assert_eq!(search.search(|o| o.output == [36]), Some(vec![2, 18]));
+        assert_eq!(search.search(|o| o.output == [401]), None);
+    }
+}
@@ -0,0 +1,200 @@
+use super::compute::{Machine, ProgramIO};
+
+extern crate scoped_threadpool;
+use itertools::Itertools;
+use scoped_threadpool::Pool;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::thread;
+
+// A chain of Intcode programs (amplifiers), each given a phase setting
+// followed by the output of the previous stage.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Wiring {
+    Linear,   // one pass through the chain
+    Feedback, // the last stage feeds the first until every stage halts
+}
+
+#[derive(Debug, Default)]
+struct StageIO {
+    input: VecDeque<i64>,
+    output: Vec<i64>,
+    last: i64,
+}
+
+impl ProgramIO for StageIO {
+    fn add_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    fn take_input(&mut self) -> i64 {
+        self.input.pop_front().unwrap()
+    }
+
+    fn write_output(&mut self, value: i64) {
+        self.output.push(value);
+        self.last = value;
+    }
+
+    fn read_output(&self) -> i64 {
+        self.last
+    }
+
+    fn has_input(&self) -> bool {
+        !self.input.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Pipeline<'a> {
+    program: &'a [i64],
+    phases: Vec<i64>,
+    stages: Option<usize>,
+    wiring: Wiring,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new(program: &'a [i64]) -> Pipeline<'a> {
+        Pipeline {
+            program,
+            phases: (0..5).collect(),
+            stages: None,
+            wiring: Wiring::Linear,
+        }
+    }
+
+    // Candidate phase settings; each is used at most once per sequence
+    pub fn phases<I: IntoIterator<Item = i64>>(mut self, phases: I) -> Pipeline<'a> {
+        self.phases = phases.into_iter().collect();
+        self
+    }
+
+    // Number of stages in the chain (defaults to the number of phases)
+    #[allow(dead_code)]
+    pub fn stages(mut self, stages: usize) -> Pipeline<'a> {
+        self.stages = Some(stages);
+        self
+    }
+
+    pub fn wiring(mut self, wiring: Wiring) -> Pipeline<'a> {
+        self.wiring = wiring;
+        self
+    }
+
+    // Run the chain once with the given phase sequence and return the
+    // last value produced by the final stage
+    pub fn run(&self, sequence: &[i64]) -> i64 {
+        let mut machines: Vec<Machine> = sequence
+            .iter()
+            .map(|_| Machine::new(self.program))
+            .collect();
+        let mut ios: Vec<StageIO> = sequence
+            .iter()
+            .map(|phase| {
+                let mut io = StageIO::default();
+                io.add_input(*phase);
+                io
+            })
+            .collect();
+        // the first stage starts with a signal of 0
+        let mut signal = vec![0];
+
+        loop {
+            let mut progress = false;
+            for (machine, io) in machines.iter_mut().zip(ios.iter_mut()) {
+                io.input.extend(signal.drain(..));
+                machine.resume(io);
+                signal = io.output.drain(..).collect();
+                progress |= !signal.is_empty();
+            }
+            // stop when halted, or when every stage is stuck waiting for input
+            if self.wiring == Wiring::Linear || !progress || machines.iter().all(|m| m.halted()) {
+                return ios.last().map_or(0, |io| io.read_output());
+            }
+        }
+    }
+
+    // Try every ordering of the phase settings; return the best sequence and its signal
+    pub fn search(&self) -> Option<(Vec<i64>, i64)> {
+        let sequences: Vec<Vec<i64>> = self
+            .phases
+            .iter()
+            .copied()
+            .permutations(self.stages.unwrap_or(self.phases.len()))
+            .unique()
+            .collect();
+        let threads = thread::available_parallelism().map_or(4, |n| n.get());
+        let chunk = (sequences.len() / threads).max(1);
+
+        // (signal, index of sequence): ties go to the earliest sequence
+        let best: Mutex<Option<(i64, usize)>> = Mutex::new(None);
+        let mut pool = Pool::new(threads as u32);
+        pool.scoped(|scope| {
+            for (c, work) in sequences.chunks(chunk).enumerate() {
+                let best = &best;
+                scope.execute(move || {
+                    for (j, sequence) in work.iter().enumerate() {
+                        let candidate = (self.run(sequence), c * chunk + j);
+                        let mut best = best.lock().unwrap();
+                        let better = match *best {
+                            Some((max, idx)) => {
+                                candidate.0 > max || (candidate.0 == max && candidate.1 < idx)
+                            }
+                            None => true,
+                        };
+                        if better {
+                            *best = Some(candidate);
+                        }
+                    }
+                });
+            }
+        });
+
+        best.into_inner()
+            .unwrap()
+            .map(|(signal, idx)| (sequences[idx].clone(), signal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<i64> {
+        input
+            .split(',')
+            .map(|x| x.trim().parse::<i64>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_run() {
+        let codes = parse("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0");
+        let pipeline = Pipeline::new(&codes);
+        assert_eq!(pipeline.run(&[4, 3, 2, 1, 0]), 43210);
+        assert_eq!(pipeline.search(), Some((vec![4, 3, 2, 1, 0], 43210)));
+    }
+
+    #[test]
+    fn test_fewer_stages() {
+        // out = 10 * in + phase
+        let codes = parse("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0");
+        let pipeline = Pipeline::new(&codes).phases(vec![1, 2, 3, 4]).stages(2);
+        assert_eq!(pipeline.search(), Some((vec![4, 3], 43)));
+        // in either order
+        let pipeline = Pipeline::new(&codes).stages(2).phases(vec![1, 2, 3, 4]);
+        assert_eq!(pipeline.search(), Some((vec![4, 3], 43)));
+    }
+
+    #[test]
+    fn test_feedback_search() {
+        let codes = parse(
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,
+            27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+        );
+        let pipeline = Pipeline::new(&codes).phases(5..=9).wiring(Wiring::Feedback);
+        assert_eq!(pipeline.run(&[9, 8, 7, 6, 5]), 139629729);
+        assert_eq!(pipeline.search(), Some((vec![9, 8, 7, 6, 5], 139629729)));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::render::Render;
+use std::cmp::Ordering;
+use std::fmt;
+
+// Space Image Format: an image is a stream of digits, one per pixel, split into
+// layers of width x height. Layers are stacked with the first in front;
+// 0 is black, 1 is white and 2 is transparent, showing what's behind.
+
+pub const BLACK: u8 = 0;
+pub const WHITE: u8 = 1;
+pub const TRANSPARENT: u8 = 2;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SifError {
+    // Width or height is zero
+    Size(usize, usize),
+    // The stream isn't a whole number of layers: (digits, layer size)
+    Length(usize, usize),
+    // Position and character of something that isn't a digit
+    Digit(usize, char),
+}
+
+impl fmt::Display for SifError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SifError::Size(w, h) => write!(f, "Image can't be {}x{}", w, h),
+            SifError::Length(len, size) => write!(
+                f,
+                "{} digits is not a whole number of {} digit layers",
+                len, size
+            ),
+            SifError::Digit(n, c) => write!(f, "Expected a digit at {}, found {:?}", n, c),
+        }
+    }
+}
+
+// How often each digit appears in a layer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayerStats {
+    counts: [usize; 10],
+}
+
+impl LayerStats {
+    fn new(layer: &[u8]) -> LayerStats {
+        let mut counts = [0; 10];
+        for d in layer {
+            counts[*d as usize] += 1;
+        }
+        LayerStats { counts }
+    }
+
+    pub fn count(&self, digit: u8) -> usize {
+        self.counts[digit as usize]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    layers: Vec<Vec<u8>>,
+}
+
+impl Image {
+    pub fn decode(input: &str, width: usize, height: usize) -> Result<Image, SifError> {
+        if width == 0 || height == 0 {
+            return Err(SifError::Size(width, height));
+        }
+        let digits = input
+            .trim()
+            .chars()
+            .enumerate()
+            .map(|(n, c)| c.to_digit(10).map(|d| d as u8).ok_or(SifError::Digit(n, c)))
+            .collect::<Result<Vec<u8>, SifError>>()?;
+
+        let size = width * height;
+        if digits.is_empty() || !digits.len().is_multiple_of(size) {
+            return Err(SifError::Length(digits.len(), size));
+        }
+        Ok(Image {
+            width,
+            height,
+            layers: digits.chunks(size).map(|l| l.to_vec()).collect(),
+        })
+    }
+
+    // Spread a black and white bitmap over several layers: pixel i is on layer
+    // i % layers, transparent in front of it, and hidden (inverted) behind it
+    #[allow(dead_code)]
+    pub fn from_bitmap(bitmap: &[Vec<bool>], layers: usize) -> Image {
+        let width = bitmap.first().map_or(0, |row| row.len());
+        if bitmap.iter().any(|row| row.len() != width) {
+            panic!("Rows of the bitmap differ in width");
+        }
+        let layers = layers.max(1);
+        let pixels: Vec<u8> = bitmap
+            .iter()
+            .flatten()
+            .map(|lit| if *lit { WHITE } else { BLACK })
+            .collect();
+
+        Image {
+            width,
+            height: bitmap.len(),
+            layers: (0..layers)
+                .map(|l| {
+                    pixels
+                        .iter()
+                        .enumerate()
+                        .map(|(i, p)| match (i % layers).cmp(&l) {
+                            Ordering::Greater => TRANSPARENT,
+                            Ordering::Equal => *p,
+                            Ordering::Less => WHITE - *p,
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn encode(&self) -> String {
+        self.layers
+            .iter()
+            .flatten()
+            .map(|d| char::from(b'0' + d))
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[allow(dead_code)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layers(&self) -> &[Vec<u8>] {
+        &self.layers
+    }
+
+    pub fn stats(&self) -> Vec<LayerStats> {
+        self.layers.iter().map(|l| LayerStats::new(l)).collect()
+    }
+
+    // Ones times twos, in the layer with the fewest zeros
+    pub fn checksum(&self) -> usize {
+        self.stats()
+            .iter()
+            .min_by_key(|s| s.count(0))
+            .map_or(0, |s| s.count(1) * s.count(2))
+    }
+
+    // The first pixel that isn't transparent, front to back
+    // (transparent if they all are)
+    fn pixel(&self, i: usize) -> u8 {
+        self.layers
+            .iter()
+            .map(|l| l[i])
+            .find(|d| *d != TRANSPARENT)
+            .unwrap_or(TRANSPARENT)
+    }
+
+    #[allow(dead_code)]
+    pub fn flatten(&self) -> Vec<u8> {
+        (0..self.width * self.height)
+            .map(|i| self.pixel(i))
+            .collect()
+    }
+}
+
+// The flattened image: '#' for white, '.' for black (and where nothing is
+// opaque, as the canvas starts out black), '%' for digits that aren't colors
+impl Render for Image {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn glyph(&self, x: usize, y: usize) -> char {
+        match self.pixel(y * self.width + x) {
+            BLACK | TRANSPARENT => '.',
+            WHITE => '#',
+            _ => '%',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render;
+
+    #[test]
+    fn test_decode() {
+        let img = Image::decode("123456789012\n", 3, 2).unwrap();
+        assert_eq!(img.layers().len(), 2);
+        assert_eq!(img.stats()[1].count(0), 1);
+        assert_eq!(img.encode(), "123456789012");
+
+        assert_eq!(Image::decode("12345", 3, 2), Err(SifError::Length(5, 6)));
+        assert_eq!(Image::decode("", 3, 2), Err(SifError::Length(0, 6)));
+        assert_eq!(Image::decode("12x456", 3, 2), Err(SifError::Digit(2, 'x')));
+        assert_eq!(Image::decode("123456", 0, 2), Err(SifError::Size(0, 2)));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bitmap: Vec<Vec<bool>> = ["#..#.", ".##..", "#...#"]
+            .iter()
+            .map(|row| row.chars().map(|c| c == '#').collect())
+            .collect();
+        let img = Image::from_bitmap(&bitmap, 4);
+        let stream = img.encode();
+        assert_eq!(stream.len(), 4 * 15);
+
+        let decoded = Image::decode(&stream, 5, 3).unwrap();
+        assert_eq!(decoded, img);
+        assert_eq!(render::ascii(&decoded, false), "#..#.\n.##..\n#...#\n");
+        assert_eq!(decoded.flatten()[..5], [1, 0, 0, 1, 0]);
+        // pixels 0, 4, 8 and 12 are on the first layer
+        assert_eq!(decoded.stats()[0].count(TRANSPARENT), 11);
+        assert_eq!(decoded.stats()[3].count(TRANSPARENT), 0);
+
+        // more layers than some rows are wide
+        let img = Image::from_bitmap(&bitmap, 7);
+        let decoded = Image::decode(&img.encode(), 5, 3).unwrap();
+        assert_eq!(render::ascii(&decoded, false), "#..#.\n.##..\n#...#\n");
+        assert_eq!(decoded.stats()[0].count(TRANSPARENT), 12);
+    }
+}
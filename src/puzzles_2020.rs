@@ -15,6 +15,8 @@ mod day_14;
 mod day_15;
 mod day_16;
 
+use crate::warn;
+
 pub fn run(day: i32) {
     match day {
         1 => day_01::run(),
@@ -35,6 +37,6 @@ pub fn run(day: i32) {
         16 => day_16::run(),
 
         // Handle the rest of cases
-        _ => println!("Nothing to see here"),
+        _ => warn!("Nothing to see here"),
     }
 }
@@ -1,8 +1,10 @@
+use crate::debug;
+use crate::grid::Grid;
 use crate::puzzle_input;
 
 pub fn run() {
     let input = puzzle_input::read_all_lines("./input/2020-d03-input1.txt");
-    let mut grid = Grid::new(input);
+    let mut grid = Slope::new(input);
 
     let t1 = grid.traverse_badly(3, 1);
     println!("** Part 1 Final: {:?}", t1);
@@ -15,22 +17,13 @@ pub fn run() {
 }
 
 #[derive(Clone, Debug)]
-struct Grid {
-    data: Vec<Vec<char>>,
-    cols: usize,
-    y_max: usize,
+struct Slope {
+    data: Grid<char>,
 }
-impl Grid {
-    pub fn new(values: Vec<String>) -> Grid {
-        let mut data: Vec<Vec<char>> = Vec::with_capacity(values.len());
-        for row in values.iter() {
-            data.push(row.chars().collect());
-        }
-
-        Grid {
-            data,
-            cols: values[0].len(),
-            y_max: values.len() - 1,
+impl Slope {
+    pub fn new(values: Vec<String>) -> Slope {
+        Slope {
+            data: Grid::parse(&values, |c| c),
         }
     }
 
@@ -38,15 +31,16 @@ impl Grid {
         let mut x = 0;
         let mut y = 0;
         let mut trees = 0;
-        while y < self.y_max {
+        while y + j < self.data.height() {
             x += i;
             y += j;
-            if self.data[y][x % self.cols] == '#' {
+            // the pattern repeats to the right
+            if self.data[(y, x % self.data.width())] == '#' {
                 trees += 1;
             }
         }
 
-        println!("Found {} trees using right {}, down {}", trees, i, j);
+        debug!("Found {} trees using right {}, down {}", trees, i, j);
         trees
     }
 }
@@ -69,7 +63,7 @@ mod tests {
         #...##....#
         .#..#...#.#";
 
-        let mut grid = Grid::new(
+        let mut grid = Slope::new(
             input
                 .split_whitespace()
                 .map(|x| x.trim().to_string())
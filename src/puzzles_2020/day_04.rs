@@ -1,6 +1,10 @@
 use crate::puzzle_input;
+use crate::scan;
+use crate::{debug, trace};
 use regex::Regex;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 pub fn run() {
     let input = puzzle_input::read_all_lines("./input/2020-d04-input1.txt");
@@ -9,138 +13,248 @@ pub fn run() {
     println!("** Part 2 Final: {:?}", valid);
 }
 
-#[derive(Debug, PartialEq)]
-enum Validity {
-    Missing,
-    Present,
-    Valid,
+// One field per line: name, required or optional, then rules separated by ';'.
+// A value has to pass every rule given for its field; fields that aren't
+// listed are ignored.
+//
+//   year 1920-2002          four digits, in the range
+//   number 1-10             digits, in the range
+//   units cm:150-193 in:59-76
+//                           a number followed by one of the units, in its range
+//   pattern #[0-9a-f]{6}    a regular expression for the whole value
+//   one-of amb blu brn      one of the words
+const PASSPORT: &str = "
+    byr required year 1920-2002
+    iyr required year 2010-2020
+    eyr required year 2020-2030
+    hgt required units cm:150-193 in:59-76
+    hcl required pattern #[0-9a-f]{6}
+    ecl required one-of amb blu brn gry grn hzl oth
+    pid required pattern [0-9]{9}
+    cid optional
+";
+
+#[derive(Clone, Debug)]
+enum Rule {
+    Year(u32, u32),
+    Number(u32, u32),
+    Units(Vec<(String, u32, u32)>),
+    // as written, and compiled to match the whole value
+    Pattern(String, Regex),
+    OneOf(Vec<String>),
 }
 
-static REQ_KEYS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
-static MISSING: Option<&Validity> = Some(&Validity::Missing);
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
 
-fn has_all_required(map: &HashMap<&str, Validity>) -> bool {
-    REQ_KEYS.iter().filter(|x| !map.contains_key(*x)).count() == 0
+fn in_range(s: &str, min: u32, max: u32) -> bool {
+    is_digits(s) && s.parse::<u32>().is_ok_and(|n| (min..=max).contains(&n))
 }
 
-fn is_valid(map: &HashMap<&str, Validity>) -> bool {
-    REQ_KEYS
-        .iter()
-        .filter(|x| *map.get(*x).or(MISSING).unwrap() != Validity::Valid)
-        .count()
-        == 0
+fn parse_range(s: &str) -> Result<(u32, u32), String> {
+    scan!(s, "{}-{}", u32, u32).map_err(|e| format!("Expected a range like 1-10: {}", e))
 }
 
-fn check<'a>(map: &mut HashMap<&'a str, Validity>, line: &str) {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"^([a-z]*):(.*)$").unwrap();
-        static ref YR: Regex = Regex::new(r"^\d{4}$").unwrap();
-        static ref HT: Regex = Regex::new(r"^(\d+)(cm|in)$").unwrap();
-        static ref HCL: Regex = Regex::new(r"^#[a-f0-9]{6}$").unwrap();
-        static ref ECL: Regex = Regex::new(r"^(?:amb|blu|brn|gry|grn|hzl|oth)$").unwrap();
-        static ref PID: Regex = Regex::new(r"^\d{9}$").unwrap();
+impl Rule {
+    fn check(&self, value: &str) -> bool {
+        match self {
+            Rule::Year(min, max) => value.len() == 4 && in_range(value, *min, *max),
+            Rule::Number(min, max) => in_range(value, *min, *max),
+            Rule::Units(units) => units.iter().any(|(unit, min, max)| {
+                value
+                    .strip_suffix(unit.as_str())
+                    .is_some_and(|n| in_range(n, *min, *max))
+            }),
+            Rule::Pattern(_, regex) => regex.is_match(value),
+            Rule::OneOf(words) => words.iter().any(|w| w == value),
+        }
     }
+}
 
-    line.split_whitespace().for_each(|x| {
-        for cap in RE.captures_iter(x) {
-            match &cap[1] {
-                "byr" => map.insert(
-                    "byr",
-                    if YR.is_match(&cap[2]) && in_year_range(&cap[2], 1920, 2002) {
-                        Validity::Valid
-                    } else {
-                        Validity::Present
-                    },
-                ),
-                "iyr" => map.insert(
-                    "iyr",
-                    if YR.is_match(&cap[2]) && in_year_range(&cap[2], 2010, 2020) {
-                        Validity::Valid
-                    } else {
-                        Validity::Present
-                    },
-                ),
-                "eyr" => map.insert(
-                    "eyr",
-                    if YR.is_match(&cap[2]) && in_year_range(&cap[2], 2020, 2030) {
-                        Validity::Valid
-                    } else {
-                        Validity::Present
-                    },
-                ),
-                "hgt" => map.insert(
-                    "hgt",
-                    if let Some(ht_caps) = HT.captures(&cap[2]) {
-                        if in_height_range(&ht_caps[1], &ht_caps[2]) {
-                            Validity::Valid
-                        } else {
-                            Validity::Present
-                        }
-                    } else {
-                        Validity::Present
-                    },
-                ),
-                "hcl" => map.insert(
-                    "hcl",
-                    if HCL.is_match(&cap[2]) {
-                        Validity::Valid
-                    } else {
-                        Validity::Present
-                    },
-                ),
-                "ecl" => map.insert(
-                    "ecl",
-                    if ECL.is_match(&cap[2]) {
-                        Validity::Valid
-                    } else {
-                        Validity::Present
-                    },
-                ),
-                "pid" => map.insert(
-                    "pid",
-                    if PID.is_match(&cap[2]) {
-                        Validity::Valid
-                    } else {
-                        Validity::Present
-                    },
-                ),
-                "cid" => map.insert("cid", Validity::Present),
-                _ => None,
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Rule, String> {
+        let (name, args) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        let args = args.trim();
+        match name {
+            "year" => parse_range(args).map(|(min, max)| Rule::Year(min, max)),
+            "number" => parse_range(args).map(|(min, max)| Rule::Number(min, max)),
+            "units" => args
+                .split_whitespace()
+                .map(|unit| {
+                    scan!(unit, "{}:{}-{}", String, u32, u32)
+                        .map_err(|e| format!("Expected unit:min-max: {}", e))
+                })
+                .collect::<Result<Vec<_>, String>>()
+                .map(Rule::Units),
+            "pattern" => Regex::new(&format!("^(?:{})$", args))
+                .map(|regex| Rule::Pattern(args.to_string(), regex))
+                .map_err(|e| format!("Bad pattern {:?}: {}", args, e)),
+            "one-of" => Ok(Rule::OneOf(
+                args.split_whitespace().map(String::from).collect(),
+            )),
+            _ => Err(format!("Unknown rule: {:?}", name)),
+        }
+    }
+}
+
+// The rule as it is written in a schema
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Year(min, max) => write!(f, "year {}-{}", min, max),
+            Rule::Number(min, max) => write!(f, "number {}-{}", min, max),
+            Rule::Units(units) => {
+                write!(f, "units")?;
+                for (unit, min, max) in units {
+                    write!(f, " {}:{}-{}", unit, min, max)?;
+                }
+                Ok(())
+            }
+            Rule::Pattern(source, _) => write!(f, "pattern {}", source),
+            Rule::OneOf(words) => write!(f, "one-of {}", words.join(" ")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Field {
+    name: String,
+    required: bool,
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Failure {
+    Missing(String),
+    // field, value, and the rule it doesn't pass
+    Invalid(String, String, String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Missing(field) => write!(f, "{} is missing", field),
+            Failure::Invalid(field, value, rule) => {
+                write!(f, "{}:{} fails {}", field, value, rule)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Schema {
+    fields: Vec<Field>,
+}
+
+impl FromStr for Schema {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Schema, String> {
+        let mut fields = Vec::new();
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let at_line = |e: String| format!("line {}: {}", n + 1, e);
+            let mut words = line.splitn(3, ' ');
+            let name = words.next().unwrap().to_string();
+            let required = match words.next() {
+                Some("required") => true,
+                Some("optional") => false,
+                x => return Err(at_line(format!("Expected required or optional: {:?}", x))),
             };
+            let rules = words
+                .next()
+                .unwrap_or("")
+                .split(';')
+                .filter(|r| !r.trim().is_empty())
+                .map(|r| r.parse().map_err(at_line))
+                .collect::<Result<Vec<Rule>, String>>()?;
+            fields.push(Field {
+                name,
+                required,
+                rules,
+            });
         }
-    });
+        Ok(Schema { fields })
+    }
 }
 
-fn in_year_range(y: &str, min: i32, max: i32) -> bool {
-    let year = y.parse::<i32>().unwrap();
-    min <= year && year <= max
+impl Schema {
+    // Every rule the record breaks, field by field
+    fn check(&self, record: &HashMap<String, String>) -> Vec<Failure> {
+        let mut failures = Vec::new();
+        for field in self.fields.iter() {
+            match record.get(&field.name) {
+                None if field.required => failures.push(Failure::Missing(field.name.clone())),
+                None => {}
+                Some(value) => {
+                    failures.extend(field.rules.iter().filter(|rule| !rule.check(value)).map(
+                        |rule| {
+                            Failure::Invalid(field.name.clone(), value.clone(), rule.to_string())
+                        },
+                    ))
+                }
+            }
+        }
+        failures
+    }
 }
 
-fn in_height_range(h: &str, u: &str) -> bool {
-    let height = h.parse::<i32>().unwrap();
-    (u == "cm" && 150 <= height && height <= 193) || (u == "in" && 59 <= height && height <= 76)
+// key:value pairs, records separated by blank lines: (first line, fields)
+fn records(batch: &[String]) -> Vec<(usize, HashMap<String, String>)> {
+    let mut records = Vec::new();
+    let mut current: Option<(usize, HashMap<String, String>)> = None;
+    for (n, line) in batch.iter().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            records.extend(current.take());
+            continue;
+        }
+        let (_, fields) = current.get_or_insert_with(|| (n + 1, HashMap::new()));
+        for pair in line.split_whitespace() {
+            match scan!(pair, "{}:{}", String, String) {
+                Ok((key, value)) => {
+                    fields.insert(key, value);
+                }
+                Err(e) => trace!("line {}: ignoring {:?}: {}", n + 1, pair, e),
+            }
+        }
+    }
+    records.extend(current);
+    records
 }
 
-fn validate(batch: &[String]) -> (i32, i32) {
-    let mut map: HashMap<&str, Validity> = HashMap::new();
+// (records with every required field, records that are also valid)
+fn validate_with(schema: &Schema, batch: &[String]) -> (i32, i32) {
     let mut present = 0;
     let mut valid = 0;
-    for line in batch {
-        let s = line.trim();
-        if s.is_empty() {
-            present += if has_all_required(&map) { 1 } else { 0 };
-            valid += if is_valid(&map) { 1 } else { 0 };
-            map.clear();
+    for (line, record) in records(batch) {
+        let failures = schema.check(&record);
+        if failures.is_empty() {
+            valid += 1;
         } else {
-            check(&mut map, line);
+            let report: Vec<String> = failures.iter().map(|f| f.to_string()).collect();
+            debug!("record at line {}: {}", line, report.join(", "));
+        }
+        if !failures.iter().any(|f| matches!(f, Failure::Missing(_))) {
+            present += 1;
         }
     }
-    present += if has_all_required(&map) { 1 } else { 0 };
-    valid += if is_valid(&map) { 1 } else { 0 };
-
     (present, valid)
 }
 
+fn validate(batch: &[String]) -> (i32, i32) {
+    let schema = PASSPORT
+        .parse()
+        .unwrap_or_else(|e| panic!("What is this? {}", e));
+    validate_with(&schema, batch)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,4 +328,67 @@ mod tests {
         assert_eq!(required, 4);
         assert_eq!(valid, 4);
     }
+
+    #[test]
+    fn test_schema_diagnostics() {
+        let schema: Schema = PASSPORT.parse().unwrap();
+        let input = puzzle_input::split_string(
+            "hgt:170 ecl:amb byr:1926 iyr:2018
+            eyr:1972 hcl:#18171d pid:186cm cid:100",
+        );
+        let passports = records(&input);
+        assert_eq!(passports.len(), 1);
+        let failures: Vec<String> = schema
+            .check(&passports[0].1)
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        assert_eq!(
+            failures,
+            vec![
+                "eyr:1972 fails year 2020-2030",
+                "hgt:170 fails units cm:150-193 in:59-76",
+                "pid:186cm fails pattern [0-9]{9}",
+            ]
+        );
+        assert_eq!(
+            schema.check(&HashMap::new())[0],
+            Failure::Missing("byr".to_string())
+        );
+
+        // a new variant is only a different schema
+        let north_pole: Schema = "
+            pid required number 1-999999999; pattern [0-9]{9}
+            ecl optional one-of amb blu
+            cid required"
+            .parse()
+            .unwrap();
+        let input = puzzle_input::split_string("pid:000000001 cid:7\n\npid:012345678 ecl:red");
+        assert_eq!(validate_with(&north_pole, &input), (1, 1));
+        let failures = north_pole.check(&records(&input)[1].1);
+        assert_eq!(
+            failures,
+            vec![
+                Failure::Invalid(
+                    "ecl".to_string(),
+                    "red".to_string(),
+                    "one-of amb blu".to_string()
+                ),
+                Failure::Missing("cid".to_string()),
+            ]
+        );
+
+        let err = "byr required\nhgt sometimes".parse::<Schema>().unwrap_err();
+        assert!(err.starts_with("line 2: "), "{}", err);
+        let err = "byr required year 1920".parse::<Schema>().unwrap_err();
+        assert!(err.starts_with("line 1: Expected a range"), "{}", err);
+        assert!("byr required colour red".parse::<Schema>().is_err());
+        let err = "hcl required pattern #[0-9".parse::<Schema>().unwrap_err();
+        assert!(err.starts_with("line 1: Bad pattern"), "{}", err);
+
+        // patterns match the whole value
+        let hair: Rule = "pattern #[0-9a-f]{6}|none".parse().unwrap();
+        assert!(hair.check("#623a2f") && hair.check("none"));
+        assert!(!hair.check("#623a2f0") && !hair.check("x#623a2f"));
+    }
 }
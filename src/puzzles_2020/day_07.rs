@@ -1,4 +1,5 @@
 use crate::puzzle_input;
+use crate::warn;
 
 use petgraph::algo::has_path_connecting;
 use petgraph::graphmap::DiGraphMap;
@@ -35,7 +36,7 @@ fn parse_input(input_ref: &str) -> DiGraphMap<&str, i32> {
                 }
             }
         } else {
-            println!("No predicate: {:?}", entry);
+            warn!("No predicate: {:?}", entry);
         }
     }
     graph
@@ -1,5 +1,6 @@
+use crate::cycle::{self, Outcome};
 use crate::puzzle_input;
-use std::collections::HashSet;
+use crate::{debug, error};
 
 pub fn run() {
     let input = puzzle_input::read_all_lines("./input/2020-d08-input1.txt");
@@ -30,22 +31,23 @@ pub fn try_repair(program: &[String]) -> i32 {
 
 pub fn detect_loop(program: &[String]) -> (i32, bool) {
     let mut console = GameConsole::new(program);
-    let mut set = HashSet::new();
-    let mut inf = false;
 
-    while let Ok(position) = console.step() {
-        if !set.insert(position) {
-            println!("Infinite loop, repeating {:?}", console.position);
-            inf = true;
-            break;
+    // The accumulator doesn't change where the program goes next, so the position is the state
+    let outcome = cycle::run_until_repeat(
+        console.position,
+        |_| console.step().ok().filter(|p| *p < program.len()),
+        |p| *p,
+    );
+    match outcome {
+        Outcome::Cycle(_, position) => {
+            debug!("Infinite loop, repeating {:?}", position);
+            (console.accumulator, true)
         }
-        if position >= console.instructions.len() {
-            println!("The End!");
-            inf = false;
-            break;
+        Outcome::Halted(..) => {
+            debug!("The End!");
+            (console.accumulator, false)
         }
     }
-    (console.accumulator, inf)
 }
 
 struct GameConsole<'a> {
@@ -100,7 +102,7 @@ impl<'a> GameConsole<'a> {
                     }
                     _ => {
                         let msg = format!("Unknown operation {:?}", op);
-                        println!("{}", msg);
+                        error!("{}", msg);
                         Err(msg)
                     }
                 }
@@ -1,5 +1,6 @@
+use crate::debug;
+use crate::memo::Memo;
 use crate::puzzle_input;
-use std::collections::HashMap;
 
 pub fn run() {
     let mut input: Vec<i32> = puzzle_input::read_all_lines("./input/2020-d10-input1.txt")
@@ -11,33 +12,30 @@ pub fn run() {
 }
 
 fn count_all_combinations(list: &[i32]) -> i64 {
-    let mut map: HashMap<usize, i64> = HashMap::new();
-    count_paths(list, &mut map, 0)
+    // Avoid re-doing work: remember the number of paths from each element
+    let mut memo = Memo::new();
+    let total = memo.get(0, &|i, paths| count_paths(list, *i, paths));
+    debug!("{}", memo);
+    total
 }
 
-fn count_paths(list: &[i32], map: &mut HashMap<usize, i64>, i: usize) -> i64 {
+fn count_paths(list: &[i32], i: usize, paths: &mut dyn FnMut(usize) -> i64) -> i64 {
     // Last element. There is only one path to it.
     // Backtrack begins here
     if i == list.len() - 1 {
         return 1;
     }
-    // Avoid re-doing work
-    if let Some(x) = map.get(&i) {
-        return *x;
-    }
     // DFS traversal along valid paths
     // list is in sorted order.. so as soon as the diffs are > 3,
     // we've run out of valid values
     let mut total: i64 = 0;
     for j in i + 1..list.len() {
         if list[j] - list[i] <= 3 {
-            total += count_paths(list, map, j);
+            total += paths(j);
         } else {
             break;
         }
     }
-    // remember for later
-    map.insert(i, total);
     total
 }
 
@@ -61,7 +59,7 @@ fn find_distribution(list: &mut Vec<i32>) -> Result<i32, String> {
         }
     }
 
-    println!("{:?}", result);
+    debug!("{:?}", result);
     Ok(result.0 * result.1)
 }
 
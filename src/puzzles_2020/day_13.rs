@@ -1,3 +1,5 @@
+use crate::debug;
+use crate::math;
 use crate::puzzle_input;
 
 pub fn run() {
@@ -33,7 +35,7 @@ fn read_bus_schedule(input: &[String]) -> (i64, Vec<Option<i64>>) {
             }
         })
         .collect();
-    println!("Time: {:?}, Buses: ${:?}", time, buses);
+    debug!("Time: {:?}, Buses: {:?}", time, buses);
     (time, buses)
 }
 
@@ -67,15 +69,14 @@ fn find_earliest_bus_sequence(schedule: &[Option<i64>]) -> i64 {
         .map(|x| (x.0 as i64, x.1.unwrap() as i64))
         .collect();
 
-    let mut t = 0;
-    let mut lcm = 1;
-    for (offset, duration) in buses {
-        while (t + offset) % duration != 0 {
-            t += lcm;
-        }
-        lcm *= duration;
-    }
-    t
+    // bus `id` leaves `offset` minutes after t: t = -offset (mod id)
+    let (t, _) = math::crt(
+        buses
+            .iter()
+            .map(|(offset, id)| (-*offset as i128, *id as i128)),
+    )
+    .expect("No timestamp satisfies every bus");
+    t as i64
 }
 
 #[cfg(test)]
@@ -93,5 +94,9 @@ mod tests {
         let (earliest, schedule) = read_bus_schedule(&input);
         assert_eq!(find_earliest_bus(earliest, &schedule), 295);
         assert_eq!(find_earliest_bus_sequence(&schedule), 1068781);
+        assert_eq!(
+            find_earliest_bus_sequence(&[Some(17), None, Some(13), Some(19)]),
+            3417
+        );
     }
 }
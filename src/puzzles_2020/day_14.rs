@@ -1,5 +1,6 @@
+use crate::bits::BitWriter;
 use crate::puzzle_input;
-use regex::Regex;
+use crate::scan;
 use std::collections::HashMap;
 
 const DEFAULT_MASK: &str = "XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX";
@@ -10,57 +11,54 @@ pub fn run() {
     println!("** Part 2 Final: {:?}", run_init_program(&input, 2));
 }
 
-fn apply_mask_to_value(mask: &str, val: u64) -> u64 {
-    let bval = format!("{:036b}", val);
-    let mut result = String::with_capacity(bval.len());
-    for (x, y) in mask.chars().zip(bval.chars()) {
-        result.push(if x == 'X' { y } else { x });
+// The mask as bit patterns: which bits are forced to 1, and which are floating (X)
+fn parse_mask(mask: &str) -> (u64, u64) {
+    let mut ones = BitWriter::new();
+    let mut floating = BitWriter::new();
+    for c in mask.chars() {
+        ones.write_bit(c == '1');
+        floating.write_bit(c == 'X');
     }
-    u64::from_str_radix(result.as_str(), 2).unwrap()
+    let n = ones.len();
+    (
+        ones.reader().read_bits(n).unwrap(),
+        floating.reader().read_bits(n).unwrap(),
+    )
+}
+
+fn apply_mask_to_value(mask: &str, val: u64) -> u64 {
+    let (ones, floating) = parse_mask(mask);
+    (val & floating) | ones
 }
 
 fn apply_mask_to_address(mask: &str, val: u64) -> Vec<u64> {
-    let bval = format!("{:036b}", val);
-    let mut values: Vec<String> = vec!["".to_string()];
-    for (x, y) in mask.chars().zip(bval.chars()) {
-        let mut extra: Vec<String> = vec![];
-        if x == 'X' {
-            for value in values.iter_mut() {
-                let mut z = value.clone();
-                z.push('1');
-                extra.push(z);
+    let (ones, floating) = parse_mask(mask);
+    let base = (val | ones) & !floating;
 
-                value.push('0');
-            }
-        } else {
-            for value in values.iter_mut() {
-                value.push(if x == '1' { '1' } else { y });
-            }
+    // every subset of the floating bits, counting down from all of them
+    let mut values = Vec::with_capacity(1 << floating.count_ones());
+    let mut subset = floating;
+    loop {
+        values.push(base | subset);
+        if subset == 0 {
+            break;
         }
-        values.extend(extra);
+        subset = (subset - 1) & floating;
     }
-
     values
-        .iter()
-        .map(|x| u64::from_str_radix(x.as_str(), 2).unwrap())
-        .collect()
 }
 
 fn run_init_program(input: &[String], v: i32) -> u64 {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"mem\[(\d+)\]").unwrap();
-    }
     let mut mask = DEFAULT_MASK;
     let mut memory: HashMap<u64, u64> = HashMap::new();
-    for line in input {
-        let mut split = line.split(" = ");
-        let instruction = split.next().unwrap();
-        let value = split.next().unwrap();
-        if instruction == "mask" {
-            mask = value;
-        } else if let Some(cap) = RE.captures(instruction) {
-            let addr = cap.get(1).unwrap().as_str().parse::<u64>().unwrap();
-            let x = value.parse::<u64>().unwrap();
+    for (n, line) in input.iter().enumerate() {
+        if line.is_empty() {
+            continue;
+        } else if let Ok(caps) = scan::split("mask = {}", line) {
+            mask = caps[0].text;
+        } else {
+            let (addr, x) = scan!(line, "mem[{}] = {}", u64, u64)
+                .unwrap_or_else(|e| panic!("What is this? {}", e.at_line(n + 1)));
             if v == 2 {
                 let addresses = apply_mask_to_address(mask, addr);
                 for a in addresses {
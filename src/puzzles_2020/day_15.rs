@@ -1,3 +1,4 @@
+use crate::debug;
 use std::collections::HashMap;
 
 pub fn run() {
@@ -18,7 +19,7 @@ fn find_number(seed: &[u32], target: u32) -> u32 {
         last = *s;
         t += 1;
     }
-    println!("Starting: {:?} {:?} .. target {:?}", t, last, target);
+    debug!("Starting: {:?} {:?} .. target {:?}", t, last, target);
     loop {
         let when = memory.insert(last, t - 1);
         if when == None {
@@ -1,8 +1,8 @@
 use crate::puzzle_input;
-use regex::Match;
-use regex::Regex;
+use crate::ranges::RangeSet;
+use crate::scan;
+use crate::warn;
 use std::collections::HashMap;
-use std::ops::RangeInclusive;
 
 pub fn run() {
     let input = puzzle_input::read_string("./input/2020-d16-input1.txt");
@@ -51,10 +51,6 @@ fn parse_input(input: &str) -> (Rules, Ticket, Vec<Ticket>) {
     (rules, my_ticket, tickets)
 }
 
-fn to_u32(s: Option<Match>) -> u32 {
-    s.unwrap().as_str().parse::<u32>().unwrap()
-}
-
 fn validate(rules: &Rules, tickets: &[Ticket]) -> u32 {
     tickets
         .iter()
@@ -67,26 +63,33 @@ fn validate(rules: &Rules, tickets: &[Ticket]) -> u32 {
 
 #[derive(Debug)]
 struct Rules {
-    bounds: HashMap<String, Vec<RangeInclusive<u32>>>,
+    bounds: HashMap<String, RangeSet<u32>>,
+    // every value allowed by at least one field
+    valid: RangeSet<u32>,
     placement: HashMap<String, Vec<usize>>,
     num_fields: usize,
 }
 impl Rules {
     fn new(input: &str) -> Rules {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"([^:]*): (\d+)-(\d+) or (\d+)-(\d+)$").unwrap();
-        }
         let mut result = Rules {
             bounds: HashMap::new(),
+            valid: RangeSet::new(),
             placement: HashMap::new(),
             num_fields: 0,
         };
         for line in input.lines() {
-            if let Some(x) = RE.captures(line.trim()) {
-                let name = x.get(1).unwrap().as_str();
-                let bound1 = std::ops::RangeInclusive::new(to_u32(x.get(2)), to_u32(x.get(3)));
-                let bound2 = std::ops::RangeInclusive::new(to_u32(x.get(4)), to_u32(x.get(5)));
-                result.bounds.insert(name.to_string(), vec![bound1, bound2]);
+            if let Ok((name, a, b, c, d)) = scan!(
+                line.trim(),
+                "{}: {}-{} or {}-{}",
+                String,
+                u32,
+                u32,
+                u32,
+                u32
+            ) {
+                let bounds: RangeSet<u32> = vec![a..=b, c..=d].into_iter().collect();
+                result.valid = result.valid.union(&bounds);
+                result.bounds.insert(name, bounds);
             }
         }
         result
@@ -104,7 +107,7 @@ impl Rules {
                 let valid = tickets
                     .iter()
                     .map(|x| x.fields[i])
-                    .filter(|x| b.iter().any(|y| y.contains(x)))
+                    .filter(|x| b.contains(*x))
                     .count();
 
                 if valid == all_tickets {
@@ -123,7 +126,7 @@ impl Rules {
                 name = entry.0.to_string();
                 field = entry.1[0];
             } else {
-                println!("Unable to find a field with only one remaining match");
+                warn!("Unable to find a field with only one remaining match");
                 break;
             }
 
@@ -157,13 +160,7 @@ impl Ticket {
         let result: (u32, u32) = (0, 0);
         self.fields
             .iter()
-            .filter(|x| {
-                !rules
-                    .bounds
-                    .values()
-                    .flat_map(|y| y.iter())
-                    .any(|y| y.contains(x))
-            })
+            .filter(|x| !rules.valid.contains(**x))
             .fold(result, |(mut count, mut sum), x| {
                 count += 1;
                 sum += x;
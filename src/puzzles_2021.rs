@@ -16,6 +16,8 @@ mod day_15;
 mod day_16;
 mod day_17;
 
+use crate::warn;
+
 pub fn run(day: i32) {
     match day {
         1 => day_01::run(),
@@ -37,6 +39,6 @@ pub fn run(day: i32) {
         17 => day_17::run(),
 
         // Handle the rest of cases
-        _ => println!("Nothing to see here"),
+        _ => warn!("Nothing to see here"),
     }
 }
@@ -1,4 +1,5 @@
 use crate::puzzle_input;
+use crate::trace;
 use std::cmp::Ordering;
 use std::convert::TryInto;
 
@@ -57,7 +58,7 @@ fn filter_bits(report: &[String], start: char, gt: char, eq: char, lt: char) ->
 
     loop {
         filtered.retain(|y| y.chars().nth(i) == Some(x));
-        // println!("{:?} {:?}, {:?}", i, x, filtered);
+        trace!("{:?} {:?}, {:?}", i, x, filtered);
         if filtered.len() == 1 {
             break;
         }
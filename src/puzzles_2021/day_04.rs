@@ -1,4 +1,5 @@
 use crate::puzzle_input;
+use crate::trace;
 use std::collections::HashMap;
 use std::slice::Iter;
 
@@ -113,7 +114,7 @@ fn play_through<'a>(draw: &'a [i32], boards: &'a mut Vec<Board>, n: usize) -> (i
             let bingo = boards[i].mark(*d);
             if bingo {
                 results.push((*d, i));
-                //println!("bingo with draw: {:?}, i: {:?} --> r: {:?}", d, i, results);
+                trace!("bingo with draw: {:?}, i: {:?} --> r: {:?}", d, i, results);
             }
         }
     }
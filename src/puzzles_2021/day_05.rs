@@ -1,7 +1,8 @@
 use crate::puzzle_input;
-use regex::Regex;
+use crate::render::Render;
+use crate::scan;
+use crate::sparse::SparseGrid;
 use std::cmp;
-use std::collections::HashMap;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2021-d05-input.txt");
@@ -15,41 +16,28 @@ pub fn run() {
 
 #[derive(Clone, Debug, PartialEq)]
 struct Grid {
-    data: HashMap<(i32, i32), i32>,
-    width: i32,
-    height: i32,
+    data: SparseGrid<i32>,
 }
 
 impl Grid {
     fn new(input: &[String], diagonals: bool) -> Grid {
-        lazy_static! {
-            static ref LINE_RE: Regex = Regex::new(r"(\d+),(\d+)[^\d]+(\d+),(\d+)").unwrap();
-        }
-
-        let mut data: HashMap<(i32, i32), i32> = HashMap::new();
-        let mut height: i32 = 0;
-        let mut width: i32 = 0;
-        for line in input {
-            let cap = LINE_RE.captures(line).unwrap();
-            let x1 = cap.get(1).unwrap().as_str().parse::<i32>().unwrap();
-            let y1 = cap.get(2).unwrap().as_str().parse::<i32>().unwrap();
-            let x2 = cap.get(3).unwrap().as_str().parse::<i32>().unwrap();
-            let y2 = cap.get(4).unwrap().as_str().parse::<i32>().unwrap();
-            height = cmp::max(y1, cmp::max(y2, height));
-            width = cmp::max(x1, cmp::max(x2, width));
+        let mut data: SparseGrid<i32> = SparseGrid::new();
+        let lines = scan::lines(input, |line| {
+            scan!(line, "{},{} -> {},{}", i32, i32, i32, i32)
+        })
+        .unwrap_or_else(|e| panic!("What is this? {}", e));
+        for (x1, y1, x2, y2) in lines {
             if x1 == x2 {
                 let b = cmp::min(y1, y2);
                 let e = cmp::max(y1, y2) + 1;
                 for y in b..e {
-                    let counter = data.entry((x1, y)).or_insert(0);
-                    *counter += 1;
+                    *data.get_or_insert_with((x1, y), || 0) += 1;
                 }
             } else if y1 == y2 {
                 let b = cmp::min(x1, x2);
                 let e = cmp::max(x1, x2) + 1;
                 for x in b..e {
-                    let counter = data.entry((x, y1)).or_insert(0);
-                    *counter += 1;
+                    *data.get_or_insert_with((x, y1), || 0) += 1;
                 }
             } else if diagonals && (x1 - x2).abs() == (y1 - y2).abs() {
                 let mut x = cmp::min(x1, x2);
@@ -57,44 +45,39 @@ impl Grid {
                 let up = if x == x1 { y1 < y2 } else { y2 < y1 };
                 let mut y = if x == x1 { y1 } else { y2 };
                 for _ in 0..range {
-                    let counter = data.entry((x, y)).or_insert(0);
-                    *counter += 1;
+                    *data.get_or_insert_with((x, y), || 0) += 1;
                     x += 1;
                     y = if up { y + 1 } else { y - 1 };
                 }
             }
         }
 
-        Grid {
-            data,
-            height: height + 1,
-            width: width + 1,
-        }
+        Grid { data }
     }
 
     fn at_least_2(&self) -> usize {
         self.data.values().filter(|x| **x >= 2).count()
     }
+}
 
-    #[allow(dead_code)]
-    fn dump(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if let Some(pt) = self.data.get(&(x, y)) {
-                    print!("{}", pt);
-                } else {
-                    print!(".");
-                }
-            }
-            println!();
-        }
-        println!();
+// How many lines cross each point, '.' where none do
+impl Render for Grid {
+    fn size(&self) -> (usize, usize) {
+        (self.data.width(), self.data.height())
+    }
+
+    fn glyph(&self, x: usize, y: usize) -> char {
+        self.data
+            .bounds()
+            .and_then(|(min, _)| self.data.get((min.0 + x as i32, min.1 + y as i32)))
+            .map_or('.', |n| std::char::from_digit(*n as u32, 36).unwrap_or('*'))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::render;
 
     #[test]
     fn test() {
@@ -112,12 +95,12 @@ mod tests {
         );
 
         let grid = Grid::new(&input, false);
-        grid.dump();
+        println!("{}", render::ascii(&grid, false));
 
         assert_eq!(5, grid.at_least_2());
 
         let grid2 = Grid::new(&input, true);
-        grid2.dump();
+        println!("{}", render::ascii(&grid2, false));
         assert_eq!(12, grid2.at_least_2());
     }
 }
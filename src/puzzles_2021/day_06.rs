@@ -1,3 +1,4 @@
+use crate::debug;
 use crate::puzzle_input;
 
 pub fn run() {
@@ -69,7 +70,7 @@ impl Calculator {
     fn compare(&self, expected: [i64; 9]) -> bool {
         let data = if self.is_a { &self.a } else { &self.b };
 
-        println!("{:?} == {:?}", expected, data);
+        debug!("{:?} == {:?}", expected, data);
         *data == expected
     }
 
@@ -1,3 +1,4 @@
+use crate::debug;
 use crate::puzzle_input;
 use std::cmp;
 
@@ -22,8 +23,8 @@ fn calculate_min_fuel(input: &mut Vec<i32>) -> (i32, i32) {
     let m1: i32 = mean.floor() as i32;
     let m2: i32 = mean.ceil() as i32;
 
-    println!("median is {:?}", median);
-    println!("mean: {:?} {:?}", m1, m2);
+    debug!("median is {:?}", median);
+    debug!("mean: {:?} {:?}", m1, m2);
 
     let single = input.iter().map(|x| (median - x).abs()).sum();
 
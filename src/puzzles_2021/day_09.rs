@@ -1,100 +1,50 @@
+use crate::grid::Grid;
 use crate::puzzle_input;
-use std::cmp;
-use std::collections::HashMap;
+use crate::render;
 use std::collections::HashSet;
-use std::hash;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2021-d09-input.txt");
-    let grid = Grid::new(&input);
+    let grid = Heights::new(&input);
 
     println!("** Part 1 Final: {:?}", grid.find_risk());
     assert_eq!(491, grid.find_risk());
     println!("** Part 2 Final: {:?}", grid.find_basins());
+    render::emit(&grid.grid);
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Point {
-    y: usize,
-    x: usize,
-    height: i32,
-}
-impl hash::Hash for Point {
-    fn hash<H: hash::Hasher>(&self, state: &mut H) {
-        self.x.hash(state);
-        self.y.hash(state);
-    }
-}
-impl cmp::PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
-    }
-}
-impl cmp::Eq for Point {}
-
 #[derive(Clone, Debug, PartialEq)]
-struct Grid {
-    data: HashMap<(usize, usize), Point>,
-    width: usize,
-    height: usize,
+struct Heights {
+    grid: Grid<i32>,
 }
-impl Grid {
-    fn new(input: &[String]) -> Grid {
-        let mut data: HashMap<(usize, usize), Point> = HashMap::new();
-        for (y, row) in input.iter().enumerate() {
-            for (x, col) in row.trim().chars().enumerate() {
-                data.insert(
-                    (y, x),
-                    Point {
-                        x,
-                        y,
-                        height: col.to_digit(10).unwrap() as i32,
-                    },
-                );
-            }
-        }
-
-        Grid {
-            data,
-            height: input.len(),
-            width: input[0].len(),
+impl Heights {
+    fn new(input: &[String]) -> Heights {
+        Heights {
+            grid: Grid::parse(input, |c| c.to_digit(10).unwrap() as i32),
         }
     }
 
-    fn neighbors(&self, p: &Point) -> Vec<&Point> {
-        let mut result: Vec<&Point> = vec![];
-        // bad things happen if you try to take a usize
-        if p.y > 0 {
-            result.push(self.data.get(&(p.y - 1, p.x)).unwrap());
-        }
-        if p.x > 0 {
-            result.push(self.data.get(&(p.y, p.x - 1)).unwrap());
-        }
-        if let Some(n) = self.data.get(&(p.y + 1, p.x)) {
-            result.push(n);
-        }
-        if let Some(n) = self.data.get(&(p.y, p.x + 1)) {
-            result.push(n);
-        }
-        result
+    fn is_low_point(&self, p: (usize, usize)) -> bool {
+        let height = self.grid[p];
+        self.grid.neighbors(p).all(|n| self.grid[n] > height)
     }
 
-    fn basin(&self, p: &Point, seen: &mut HashSet<Point>) {
-        if seen.contains(p) {
+    fn basin(&self, p: (usize, usize), seen: &mut HashSet<(usize, usize)>) {
+        if !seen.insert(p) {
             return;
         }
-        seen.insert(*p);
-        self.neighbors(p)
-            .iter()
-            .filter(|n| n.height != 9)
-            .for_each(|n| self.basin(n, seen));
+        for n in self.grid.neighbors(p) {
+            if self.grid[n] != 9 {
+                self.basin(n, seen);
+            }
+        }
     }
 
     fn find_basins(&self) -> i32 {
         let mut result: Vec<i32> = self
-            .data
-            .values()
-            .filter(|p| self.neighbors(p).iter().all(|n| n.height > p.height))
+            .grid
+            .points()
+            .filter(|p| self.is_low_point(*p))
             .map(|p| {
                 let mut seen = HashSet::new();
                 self.basin(p, &mut seen);
@@ -107,24 +57,12 @@ impl Grid {
     }
 
     fn find_risk(&self) -> i32 {
-        self.data
-            .values()
-            .filter(|p| self.neighbors(p).iter().all(|n| n.height > p.height))
-            .map(|p| p.height + 1)
+        self.grid
+            .points()
+            .filter(|p| self.is_low_point(*p))
+            .map(|p| self.grid[p] + 1)
             .sum()
     }
-
-    #[allow(dead_code)]
-    fn dump(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let p = self.data.get(&(y, x)).unwrap();
-                print!("  {:?}  ", p.height);
-            }
-            println!();
-        }
-        println!();
-    }
 }
 
 #[cfg(test)]
@@ -141,8 +79,8 @@ mod tests {
             9899965678",
         );
 
-        let grid = Grid::new(&input);
-        grid.dump();
+        let grid = Heights::new(&input);
+        println!("{}", render::ascii(&grid.grid, false));
         assert_eq!(15, grid.find_risk());
         assert_eq!(1134, grid.find_basins());
     }
@@ -1,4 +1,5 @@
 use crate::puzzle_input;
+use crate::trace;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2021-d10-input.txt");
@@ -67,7 +68,7 @@ fn completed_score(remaining: &Option<Vec<char>>) -> i64 {
                 '>' => 4,
                 _ => 0,
             })
-            .inspect(|s| println!("score {:?}", s))
+            .inspect(|s| trace!("score {:?}", s))
             .fold(0, |acc, s| acc * 5 + s)
     } else {
         0
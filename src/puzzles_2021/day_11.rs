@@ -1,7 +1,8 @@
+use crate::animation;
+use crate::automaton::{Adjacent, Automaton};
+use crate::grid::{self, OFFSETS_8};
 use crate::puzzle_input;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::ops::RangeInclusive;
+use std::fmt;
 
 pub fn run() {
     let mut grid = Grid::new(&puzzle_input::read_all_lines("./input/2021-d11-input.txt"));
@@ -23,97 +24,73 @@ pub fn run() {
     assert_eq!(244, i);
 }
 
-fn box_range(i: usize, max: usize) -> RangeInclusive<usize> {
-    std::ops::RangeInclusive::new(
-        if i == 0 { 0 } else { i - 1 },
-        if i + 1 == max { i } else { i + 1 },
-    )
+// How far along each octopus is with flashing in the current step
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Octopus {
+    Charging(u8),
+    Flashing,
+    Flashed,
 }
 
-fn neighbors(point: (usize, usize), height: usize, width: usize) -> Vec<(usize, usize)> {
-    let mut pts = Vec::with_capacity(8);
-    for y1 in box_range(point.0, height) {
-        for x1 in box_range(point.1, width) {
-            if y1 == point.0 && x1 == point.1 {
-                continue;
-            }
-            pts.push((y1, x1));
+impl Octopus {
+    fn charge(self, energy: usize) -> Octopus {
+        match self {
+            Octopus::Charging(e) if e as usize + energy > 9 => Octopus::Flashing,
+            Octopus::Charging(e) => Octopus::Charging(e + energy as u8),
+            _ => Octopus::Flashed,
+        }
+    }
+}
+
+impl fmt::Display for Octopus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Octopus::Charging(e) => write!(f, "{}", e),
+            _ => write!(f, "*"),
         }
     }
-    pts
 }
 
-#[derive(Clone, Debug, PartialEq)]
+// Flashing octopuses charge their neighbors, which may start flashing in turn
+fn spread(octopus: &Octopus, around: &[&Octopus]) -> Octopus {
+    let flashing = around.iter().filter(|o| ***o == Octopus::Flashing).count();
+    octopus.charge(flashing)
+}
+
+type Rule = fn(&Octopus, &[&Octopus]) -> Octopus;
+
 struct Grid {
-    data: HashMap<(usize, usize), i32>,
-    width: usize,
-    height: usize,
+    data: Automaton<Octopus, Adjacent, Rule>,
     flashes: usize,
 }
 
 impl Grid {
     fn new(input: &[String]) -> Grid {
-        let mut data: HashMap<(usize, usize), i32> = HashMap::new();
-        for (y, row) in input.iter().enumerate() {
-            for (x, col) in row.trim().chars().enumerate() {
-                data.insert((y, x), col.to_digit(10).unwrap() as i32);
-            }
-        }
-
+        let energy = grid::Grid::parse(input, |c| Octopus::Charging(c.to_digit(10).unwrap() as u8));
         Grid {
-            data,
-            height: input.len(),
-            width: input[0].len(),
+            data: Automaton::new(energy, Adjacent(&OFFSETS_8), spread as Rule),
             flashes: 0,
         }
     }
 
-    fn try_flash(&mut self, k: (usize, usize), flash: &mut HashSet<(usize, usize)>) {
-        if let Some(v) = self.data.get_mut(&k) {
-            if *v > 9 && flash.insert(k) {
-                for n in neighbors(k, self.height, self.width) {
-                    let vn = self.data.get_mut(&n).unwrap();
-                    *vn += 1;
-                    self.try_flash(n, flash);
-                }
-            }
-        }
+    fn energy(&self) -> &grid::Grid<Octopus> {
+        self.data.grid()
     }
 
     fn step(&mut self) -> usize {
-        let mut flash: HashSet<(usize, usize)> = HashSet::new();
-        for (_, v) in self.data.iter_mut() {
-            *v += 1;
-        }
-
-        for y in 0..self.height {
-            for x in 0..self.width {
-                self.try_flash((y, x), &mut flash)
-            }
-        }
-
-        for (_, v) in self.data.iter_mut() {
-            if *v > 9 {
-                *v = 0;
+        self.data.map(|o| *o = o.charge(1));
+        self.data.run_until_stable();
+
+        let mut flashed = 0;
+        self.data.map(|o| {
+            if *o == Octopus::Flashed {
+                flashed += 1;
+                *o = Octopus::Charging(0);
             }
-        }
-        self.flashes += flash.len();
-        flash.len()
-    }
-
-    #[allow(dead_code)]
-    fn dump(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if let Some(pt) = self.data.get(&(y, x)) {
-                    print!("{}", pt);
-                } else {
-                    print!(".");
-                }
-            }
-            println!();
-        }
-        println!();
+        });
+        self.flashes += flashed;
+        animation::frame(self.energy());
+        flashed
     }
 }
 
@@ -141,7 +118,7 @@ mod tests {
             34543",
         ));
         ex.step();
-        assert_eq!(ex_step1.data, ex.data);
+        assert_eq!(ex_step1.energy(), ex.energy());
 
         // After step 2:
         let ex_step2 = Grid::new(&puzzle_input::split_string(
@@ -152,7 +129,7 @@ mod tests {
             45654",
         ));
         ex.step();
-        assert_eq!(ex_step2.data, ex.data);
+        assert_eq!(ex_step2.energy(), ex.energy());
 
         let mut grid = Grid::new(&puzzle_input::split_string(
             "5483143223
@@ -180,7 +157,7 @@ mod tests {
             6394862637",
         ));
         grid.step();
-        assert_eq!(grid_1.data, grid.data);
+        assert_eq!(grid_1.energy(), grid.energy());
 
         let grid_2 = Grid::new(&puzzle_input::split_string(
             "8807476555
@@ -195,7 +172,7 @@ mod tests {
             8700006848",
         ));
         grid.step();
-        assert_eq!(grid_2.data, grid.data);
+        assert_eq!(grid_2.energy(), grid.energy());
 
         let grid_3 = Grid::new(&puzzle_input::split_string(
             "0050900866
@@ -210,7 +187,7 @@ mod tests {
             0021119000",
         ));
         grid.step();
-        assert_eq!(grid_3.data, grid.data);
+        assert_eq!(grid_3.energy(), grid.energy());
 
         let grid_4 = Grid::new(&puzzle_input::split_string(
             "2263031977
@@ -225,7 +202,7 @@ mod tests {
             1132230211",
         ));
         grid.step();
-        assert_eq!(grid_4.data, grid.data);
+        assert_eq!(grid_4.energy(), grid.energy());
 
         for _ in 4..10 {
             grid.step();
@@ -243,7 +220,7 @@ mod tests {
             0532250600
             0032240000",
         ));
-        assert_eq!(grid_10.data, grid.data);
+        assert_eq!(grid_10.energy(), grid.energy());
         assert_eq!(204, grid.flashes);
 
         for _ in 0..10 {
@@ -262,7 +239,7 @@ mod tests {
             6000000364
             4600009543",
         ));
-        assert_eq!(grid_20.data, grid.data);
+        assert_eq!(grid_20.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -280,7 +257,7 @@ mod tests {
             9444447111
             7944446119",
         ));
-        assert_eq!(grid_30.data, grid.data);
+        assert_eq!(grid_30.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -298,7 +275,7 @@ mod tests {
             2222222976
             2222222762",
         ));
-        assert_eq!(grid_40.data, grid.data);
+        assert_eq!(grid_40.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -316,7 +293,7 @@ mod tests {
             6800000633
             5680000538",
         ));
-        assert_eq!(grid_50.data, grid.data);
+        assert_eq!(grid_50.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -334,7 +311,7 @@ mod tests {
             1175447111
             1115446111",
         ));
-        assert_eq!(grid_60.data, grid.data);
+        assert_eq!(grid_60.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -352,7 +329,7 @@ mod tests {
             5722223475
             4572222754",
         ));
-        assert_eq!(grid_70.data, grid.data);
+        assert_eq!(grid_70.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -370,7 +347,7 @@ mod tests {
             0000000800
             0000000000",
         ));
-        assert_eq!(grid_80.data, grid.data);
+        assert_eq!(grid_80.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -388,7 +365,7 @@ mod tests {
             3387779333
             3333333333",
         ));
-        assert_eq!(grid_90.data, grid.data);
+        assert_eq!(grid_90.energy(), grid.energy());
 
         for _ in 0..10 {
             grid.step();
@@ -407,7 +384,7 @@ mod tests {
             7922286866
             6789998766",
         ));
-        assert_eq!(grid_100.data, grid.data);
+        assert_eq!(grid_100.energy(), grid.energy());
         assert_eq!(1656, grid.flashes);
 
         let mut flashes = 0;
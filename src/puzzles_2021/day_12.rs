@@ -1,6 +1,6 @@
 use crate::puzzle_input;
+use crate::scan;
 use petgraph::graphmap::UnGraphMap;
-use regex::Regex;
 use std::collections::HashMap;
 
 pub fn run() {
@@ -12,11 +12,10 @@ pub fn run() {
 
 fn parse(input: &[String]) -> UnGraphMap<&str, i32> {
     let mut g = UnGraphMap::new();
-    for line in input {
-        let mut i = line.split('-');
-        let a = i.next().unwrap();
-        let b = i.next().unwrap();
-        g.add_edge(a, b, 1);
+    for (n, line) in input.iter().enumerate() {
+        let caves = scan::split("{}-{}", line)
+            .unwrap_or_else(|e| panic!("What is this? {}", e.at_line(n + 1)));
+        g.add_edge(caves[0].text, caves[1].text, 1);
     }
     g
 }
@@ -69,8 +68,9 @@ pub trait Visitor {
     fn is_visiting(&mut self, n: &str) -> bool;
 }
 
-lazy_static! {
-    static ref LOWER: Regex = Regex::new(r"[a-z]+").unwrap();
+// Small caves have lowercase names
+fn is_small(n: &str) -> bool {
+    n.chars().any(|c| c.is_ascii_lowercase())
 }
 
 #[derive(Clone, Debug)]
@@ -86,7 +86,7 @@ impl DefaultVisitor {
 }
 impl Visitor for DefaultVisitor {
     fn set_visiting(&mut self, n: &str, visit: bool) {
-        if LOWER.is_match(n) {
+        if is_small(n) {
             self.visiting.insert(n.to_string(), visit);
         }
     }
@@ -114,7 +114,7 @@ impl PermissiveVisitor {
 }
 impl Visitor for PermissiveVisitor {
     fn set_visiting(&mut self, n: &str, visit: bool) {
-        if LOWER.is_match(n) {
+        if is_small(n) {
             if visit {
                 if let Some(prev) = self.visiting.insert(n.to_string(), visit) {
                     if prev {
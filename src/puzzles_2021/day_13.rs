@@ -1,4 +1,7 @@
+use crate::ocr;
 use crate::puzzle_input;
+use crate::render::{self, Render};
+use crate::{debug, info};
 use std::cmp;
 use std::collections::HashSet;
 
@@ -11,11 +14,13 @@ pub fn run() {
 
     println!("** Part 1 Final: {:?}", paper.dots.len());
     while let Some((axis, index)) = paper.next() {
-        println!("Folding {:?}={:?}", axis, index);
+        debug!("Folding {:?}={:?}", axis, index);
         paper.fold(axis, index);
     }
-    paper.dump();
-    println!("** Part 2 Final: {:?}", 0);
+    let image = render::ascii(&paper, false);
+    info!("\n{}", image);
+    println!("** Part 2 Final: {:?}", ocr::recognize(&image));
+    render::emit(&paper);
 }
 
 #[derive(Clone, Debug)]
@@ -89,20 +94,19 @@ impl Paper {
             }
         }
     }
+}
 
-    #[allow(dead_code)]
-    fn dump(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.dots.get(&(y, x)).is_some() {
-                    print!("#");
-                } else {
-                    print!(".");
-                }
-            }
-            println!();
+impl Render for Paper {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn glyph(&self, x: usize, y: usize) -> char {
+        if self.dots.contains(&(y, x)) {
+            '#'
+        } else {
+            '.'
         }
-        println!();
     }
 }
 
@@ -137,13 +141,17 @@ mod tests {
         );
 
         let mut paper = Paper::new(&input);
-        paper.dump();
+        println!("{}", render::ascii(&paper, false));
 
         while let Some((axis, index)) = paper.next() {
             paper.fold(axis, index);
-            paper.dump();
+            println!("{}", render::ascii(&paper, false));
         }
 
         assert_eq!(16, paper.dots.len());
+        assert_eq!(
+            render::ascii(&paper, false),
+            "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....\n"
+        );
     }
 }
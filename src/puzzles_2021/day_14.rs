@@ -1,3 +1,5 @@
+use crate::debug;
+use crate::memo::Memo;
 use crate::puzzle_input;
 use itertools::Itertools;
 use std::collections::HashMap;
@@ -5,15 +7,10 @@ use std::slice::Iter;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2021-d14-input.txt");
-    let (mut totals, rules) = parse_rules(&input);
+    let rules = parse_rules(&input);
 
-    for i in 0..40 {
-        if i == 10 {
-            println!("** Part 1 Final: {:?}", totals.score());
-        }
-        totals = step(&totals, &rules);
-    }
-    println!("** Part 2 Final: {:?}", totals.score());
+    println!("** Part 1 Final: {:?}", score(&input[0], &rules, 10));
+    println!("** Part 2 Final: {:?}", score(&input[0], &rules, 40));
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -33,76 +30,121 @@ impl Rules {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-struct Totals {
-    data: HashMap<String, usize>,
-    letters: HashMap<char, usize>,
+fn parse_rules(input: &[String]) -> Rules {
+    // the template is on the first line, the rules follow
+    Rules::new(&mut input[1..].iter())
 }
-impl Totals {
-    fn new(start: &str) -> Totals {
-        let data = start
-            .chars()
-            .tuple_windows()
-            .fold(HashMap::new(), |mut acc, (a, b)| {
-                let pair = format!("{}{}", a, b);
-                let count = acc.entry(pair).or_insert(0);
-                *count += 1;
-                acc
-            });
-
-        let letters = start.chars().fold(HashMap::new(), |mut acc, c| {
-            let i = acc.entry(c).or_insert(0);
-            *i += 1;
-            acc
-        });
 
-        Totals { data, letters }
-    }
+// Letters are counted in a fixed array (A-Z) so results are cheap to copy
+type Counts = [usize; 26];
+type Key = (char, char, usize);
 
-    fn inc_letter(&mut self, letter: char, n: usize) {
-        let counter = self.letters.entry(letter).or_insert(0);
-        *counter += n;
-    }
+fn index(c: char) -> usize {
+    (c as u8 - b'A') as usize
+}
 
-    fn inc_pair(&mut self, pair: String, n: usize) {
-        let counter = self.data.entry(pair).or_insert(0);
-        *counter += n;
+// Letters inserted between a and b after the given number of steps
+fn inserted(rules: &Rules, key: &Key, expand: &mut dyn FnMut(Key) -> Counts) -> Counts {
+    let (a, b, steps) = *key;
+    let mut counts = [0; 26];
+    if steps == 0 {
+        return counts;
     }
-
-    fn score(&self) -> usize {
-        self.letters.values().max().unwrap() - self.letters.values().min().unwrap()
+    let c = *rules
+        .data
+        .get(&format!("{}{}", a, b))
+        .expect("pair wasn't found in the rules");
+    counts[index(c)] += 1;
+    for (x, y) in [(a, c), (c, b)] {
+        for (total, n) in counts.iter_mut().zip(expand((x, y, steps - 1)).iter()) {
+            *total += n;
+        }
     }
+    counts
 }
 
-fn parse_rules(input: &[String]) -> (Totals, Rules) {
-    let mut i = input.iter();
-    let totals = Totals::new(i.next().unwrap()); // start with first line
-    let rules = Rules::new(&mut i); // parse the rest
-
-    (totals, rules)
-}
-
-fn step(totals: &Totals, rules: &Rules) -> Totals {
-    let mut next = Totals::new("");
-    next.letters = totals.letters.clone();
-
-    for (pair, count) in &totals.data {
-        if let Some(v) = rules.data.get(pair) {
-            let mut i = pair.chars();
-            next.inc_pair(format!("{}{}", i.next().unwrap(), v), *count);
-            next.inc_pair(format!("{}{}", v, i.next().unwrap()), *count);
-            next.inc_letter(*v, *count);
-        } else {
-            panic!("pair wasn't found in the rules");
+fn score(template: &str, rules: &Rules, steps: usize) -> usize {
+    let template = template.trim();
+    let mut memo = Memo::new();
+    let mut counts = [0; 26];
+    for c in template.chars() {
+        counts[index(c)] += 1;
+    }
+    for (a, b) in template.chars().tuple_windows() {
+        let more = memo.get((a, b, steps), &|k, f| inserted(rules, k, f));
+        for (total, n) in counts.iter_mut().zip(more.iter()) {
+            *total += n;
         }
     }
-    next
+    debug!("{}", memo);
+
+    let present = counts.iter().filter(|n| **n > 0);
+    present.clone().max().unwrap() - present.min().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[derive(Clone, Debug, PartialEq)]
+    struct Totals {
+        data: HashMap<String, usize>,
+        letters: HashMap<char, usize>,
+    }
+    impl Totals {
+        fn new(start: &str) -> Totals {
+            let data = start
+                .chars()
+                .tuple_windows()
+                .fold(HashMap::new(), |mut acc, (a, b)| {
+                    let pair = format!("{}{}", a, b);
+                    let count = acc.entry(pair).or_insert(0);
+                    *count += 1;
+                    acc
+                });
+
+            let letters = start.chars().fold(HashMap::new(), |mut acc, c| {
+                let i = acc.entry(c).or_insert(0);
+                *i += 1;
+                acc
+            });
+
+            Totals { data, letters }
+        }
+
+        fn inc_letter(&mut self, letter: char, n: usize) {
+            let counter = self.letters.entry(letter).or_insert(0);
+            *counter += n;
+        }
+
+        fn inc_pair(&mut self, pair: String, n: usize) {
+            let counter = self.data.entry(pair).or_insert(0);
+            *counter += n;
+        }
+
+        fn score(&self) -> usize {
+            self.letters.values().max().unwrap() - self.letters.values().min().unwrap()
+        }
+    }
+
+    // Step-by-step version, tracking counts of each pair, to cross-check score
+    fn step(totals: &Totals, rules: &Rules) -> Totals {
+        let mut next = Totals::new("");
+        next.letters = totals.letters.clone();
+
+        for (pair, count) in &totals.data {
+            if let Some(v) = rules.data.get(pair) {
+                let mut i = pair.chars();
+                next.inc_pair(format!("{}{}", i.next().unwrap(), v), *count);
+                next.inc_pair(format!("{}{}", v, i.next().unwrap()), *count);
+                next.inc_letter(*v, *count);
+            } else {
+                panic!("pair wasn't found in the rules");
+            }
+        }
+        next
+    }
+
     #[test]
     fn test() {
         let input: Vec<String> = puzzle_input::split_string(
@@ -126,7 +168,8 @@ mod tests {
         CN -> C",
         );
 
-        let (mut totals, rules) = parse_rules(&input);
+        let rules = parse_rules(&input);
+        let mut totals = Totals::new(&input[0]);
 
         totals = step(&totals, &rules);
         let mut ex_totals = Totals::new("NCNBCHB");
@@ -153,5 +196,8 @@ mod tests {
             totals = step(&totals, &rules);
         }
         assert_eq!(2188189693529, totals.score());
+
+        assert_eq!(1588, score(&input[0], &rules, 10));
+        assert_eq!(2188189693529, score(&input[0], &rules, 40));
     }
 }
@@ -1,106 +1,77 @@
+use crate::grid::Grid;
 use crate::puzzle_input;
-use petgraph::algo::astar;
-use petgraph::graphmap::DiGraphMap;
-use petgraph::visit::EdgeRef;
+use crate::search;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2021-d15-input.txt");
-    let (g, end, big_end) = parse(&input);
+    let cave = Cave::new(&input);
 
-    let path = astar(&g, (0, 0), |finish| finish == end, |e| *e.weight(), |_| 0).unwrap();
-    println!("** Part 1 Final: {:?}", path.0);
-
-    let big_path = astar(
-        &g,
-        (0, 0),
-        |finish| finish == big_end,
-        |e| *e.weight(),
-        |_| 0,
-    )
-    .unwrap();
-    println!("** Part 2 Final: {:?}", big_path.0);
+    println!("** Part 1 Final: {:?}", cave.lowest_risk(1));
+    println!("** Part 2 Final: {:?}", cave.lowest_risk(5));
 }
 
-#[allow(clippy::type_complexity)]
-fn parse(
-    input: &[String],
-) -> (
-    DiGraphMap<(usize, usize), i32>,
-    (usize, usize),
-    (usize, usize),
-) {
-    let mut graph = DiGraphMap::new();
-    let height = input.len();
-    let width = input[0].len();
-    let max_height = height * 5;
-    let max_width = width * 5;
-
-    for (y, row) in input.iter().enumerate() {
-        for (x, col) in row.trim().chars().enumerate() {
-            let mut r = vec![0; 10];
-            r[0] = col.to_digit(10).unwrap() as i32;
-            for n in 1..10 {
-                r[n] = r[n - 1] + 1;
-                if r[n] > 9 {
-                    r[n] = 1;
-                }
-            }
-            add_edges(&mut graph, max_height, max_width, y, x, r[0]);
+struct Cave {
+    risk: Grid<usize>,
+}
 
-            for i in 0..5 {
-                for j in 0..5 {
-                    let r = r[i + j] as i32;
-                    let y1 = y + i * height;
-                    let x1 = x + j * width;
-                    add_edges(&mut graph, max_height, max_width, y1, x1, r);
-                }
-            }
+impl Cave {
+    fn new(input: &[String]) -> Cave {
+        Cave {
+            risk: Grid::parse(input, |c| c.to_digit(10).unwrap() as usize),
         }
     }
-    (
-        graph,
-        (height - 1, width - 1),
-        (max_height - 1, max_width - 1),
-    )
-}
 
-fn add_edges(
-    graph: &mut DiGraphMap<(usize, usize), i32>,
-    max_height: usize,
-    max_width: usize,
-    y: usize,
-    x: usize,
-    risk: i32,
-) {
-    let here = (y, x);
-    if y > 0 {
-        graph.add_edge((y - 1, x), here, risk);
-    }
-    if x > 0 {
-        graph.add_edge((y, x - 1), here, risk);
+    // The map repeats `tiles` times in each direction;
+    // each repetition to the right or down adds 1 to the risk (wrapping 9 back to 1)
+    fn risk_at(&self, pt: (usize, usize)) -> usize {
+        let (h, w) = (self.risk.height(), self.risk.width());
+        let r = self.risk[(pt.0 % h, pt.1 % w)] + pt.0 / h + pt.1 / w;
+        (r - 1) % 9 + 1
     }
-    if y < max_height - 1 {
-        graph.add_edge((y + 1, x), here, risk);
+
+    fn entering(&self, pt: &(usize, usize), max: (usize, usize)) -> Vec<((usize, usize), usize)> {
+        let mut result = Vec::with_capacity(4);
+        if pt.0 > 0 {
+            result.push((pt.0 - 1, pt.1));
+        }
+        if pt.1 > 0 {
+            result.push((pt.0, pt.1 - 1));
+        }
+        if pt.0 < max.0 {
+            result.push((pt.0 + 1, pt.1));
+        }
+        if pt.1 < max.1 {
+            result.push((pt.0, pt.1 + 1));
+        }
+        result.into_iter().map(|n| (n, self.risk_at(n))).collect()
     }
-    if x < max_width - 1 {
-        graph.add_edge((y, x + 1), here, risk);
+
+    fn lowest_risk(&self, tiles: usize) -> usize {
+        let end = (
+            self.risk.height() * tiles - 1,
+            self.risk.width() * tiles - 1,
+        );
+        // every step costs at least 1, so the manhattan distance never overestimates
+        search::astar(
+            (0, 0),
+            |p| self.entering(p, end),
+            |p| (end.0 - p.0) + (end.1 - p.1),
+            |p| *p == end,
+        )
+        .unwrap()
+        .1
     }
-}
 
-#[allow(dead_code)]
-fn dump(graph: &DiGraphMap<(usize, usize), i32>, max_height: usize, max_width: usize) {
-    for y in 0..max_height {
-        for x in 0..max_width {
-            let other = if y == 0 { (y + 1, x) } else { (y - 1, x) };
-            if let Some(w) = graph.edge_weight(other, (y, x)) {
-                print!("{:?}", *w);
-            } else {
-                print!(".");
-            }
-        }
-        println!();
+    #[allow(dead_code)]
+    fn lowest_risk_dijkstra(&self, tiles: usize) -> usize {
+        let end = (
+            self.risk.height() * tiles - 1,
+            self.risk.width() * tiles - 1,
+        );
+        search::dijkstra((0, 0), |p| self.entering(p, end), |p| *p == end)
+            .unwrap()
+            .1
     }
-    println!();
 }
 
 #[cfg(test)]
@@ -122,20 +93,11 @@ mod tests {
             2311944581",
         );
 
-        let (g, end, big_end) = parse(&input);
-
-        let path = astar(&g, (0, 0), |finish| finish == end, |e| *e.weight(), |_| 0).unwrap();
-        println!("{:?}", path);
-        assert_eq!(40, path.0);
-
-        let big_path = astar(
-            &g,
-            (0, 0),
-            |finish| finish == big_end,
-            |e| *e.weight(),
-            |_| 0,
-        )
-        .unwrap();
-        assert_eq!(315, big_path.0);
+        let cave = Cave::new(&input);
+        assert_eq!(cave.risk_at((0, 10)), 2);
+        assert_eq!(cave.risk_at((49, 49)), 9);
+        assert_eq!(40, cave.lowest_risk(1));
+        assert_eq!(315, cave.lowest_risk(5));
+        assert_eq!(315, cave.lowest_risk_dijkstra(5));
     }
 }
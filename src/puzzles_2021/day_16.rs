@@ -1,3 +1,4 @@
+use crate::bits::{self, BitReader, EndOfData};
 use crate::puzzle_input;
 extern crate num;
 
@@ -72,48 +73,17 @@ impl BitsPacket {
     }
 }
 
-fn read_header(source: &str, i: &mut usize) -> usize {
-    let start = *i;
-    *i += 3;
-    usize::from_str_radix(&source[start..*i], 2).unwrap()
+fn read_type(bits: &mut BitReader) -> Result<PacketType, EndOfData> {
+    let convert = bits.read_bits(3)?;
+    Ok(num::FromPrimitive::from_u64(convert).unwrap())
 }
 
-fn read_type(source: &str, i: &mut usize) -> PacketType {
-    let convert = read_header(source, i);
-    num::FromPrimitive::from_usize(convert).unwrap()
-}
-
-fn read_bitflag(source: &str, i: &mut usize) -> bool {
-    let flag = &source[*i..*i + 1] == "1";
-    *i += 1;
-    flag
-}
-
-fn read_len_type_id(source: &str, i: &mut usize) -> LengthTypeId {
-    let bit = &source[*i..*i + 1];
-    *i += 1;
-    match bit {
-        "0" => LengthTypeId::NumBytes,
-        _ => LengthTypeId::NumPackets,
-    }
-}
-
-fn read_literal_group<'a>(source: &'a str, i: &mut usize) -> &'a str {
-    let start = *i;
-    *i += 4;
-    &source[start..*i]
-}
-
-fn read_total_length(source: &str, i: &mut usize) -> usize {
-    let start = *i;
-    *i += 15;
-    usize::from_str_radix(&source[start..*i], 2).unwrap()
-}
-
-fn read_num_packets(source: &str, i: &mut usize) -> usize {
-    let start = *i;
-    *i += 11;
-    usize::from_str_radix(&source[start..*i], 2).unwrap()
+fn read_len_type_id(bits: &mut BitReader) -> Result<LengthTypeId, EndOfData> {
+    Ok(if bits.read_bit()? {
+        LengthTypeId::NumPackets
+    } else {
+        LengthTypeId::NumBytes
+    })
 }
 
 // Literal value packets encode a single binary number.
@@ -121,132 +91,89 @@ fn read_num_packets(source: &str, i: &mut usize) -> usize {
 // is a multiple of four bits, and then it is broken into groups of four bits.
 // Each group is prefixed by a 1 bit except the last group, which is prefixed by a 0 bit.
 // These groups of five bits immediately follow the packet header.
-fn read_packet_literal(source: &str, i: &mut usize, b_version: usize) -> BitsPacket {
-    let mut value = String::new();
+fn read_packet_literal(bits: &mut BitReader, b_version: usize) -> Result<BitsPacket, EndOfData> {
+    let mut value: i64 = 0;
     let mut fragment = true;
-    let mut chunk: &str;
 
-    while fragment && *i < source.len() - 4 {
-        fragment = read_bitflag(source, i);
-        chunk = read_literal_group(source, i);
-        value.push_str(chunk);
+    while fragment {
+        fragment = bits.read_bit()?;
+        value = value << 4 | bits.read_bits(4)? as i64;
     }
 
-    if let Ok(v) = i64::from_str_radix(&value, 2) {
-        BitsPacket {
-            b_version,
-            b_type: PacketType::Literal,
-            b_literal: Some(v),
-            b_len_type: None,
-            b_len: None,
-            b_packets: None,
-        }
-    } else {
-        panic!("Lost our way parsing a literal... ");
-    }
+    Ok(BitsPacket {
+        b_version,
+        b_type: PacketType::Literal,
+        b_literal: Some(value),
+        b_len_type: None,
+        b_len: None,
+        b_packets: None,
+    })
 }
 
-fn read_num_bytes(source: &str, i: &mut usize, b_version: usize, b_type: PacketType) -> BitsPacket {
-    let num_bytes = read_total_length(source, i);
-    let packet_start = *i;
+fn read_num_bytes(
+    bits: &mut BitReader,
+    b_version: usize,
+    b_type: PacketType,
+) -> Result<BitsPacket, EndOfData> {
+    let num_bytes = bits.read_bits(15)? as usize;
+    let packet_start = bits.position();
     let mut packets = Vec::new();
 
-    while *i - packet_start < num_bytes {
-        if let Some(packet) = read_packet(source, i) {
-            packets.push(packet);
-        } else {
-            panic!("Lost our way parsing a nested packet... ");
-        }
+    while bits.position() - packet_start < num_bytes {
+        packets.push(read_packet(bits)?);
     }
 
-    BitsPacket {
+    Ok(BitsPacket {
         b_version,
         b_type,
         b_literal: None,
         b_len_type: Some(LengthTypeId::NumBytes),
         b_len: Some(num_bytes),
         b_packets: Some(packets),
-    }
+    })
 }
 
 fn read_num_subpackets(
-    source: &str,
-    i: &mut usize,
+    bits: &mut BitReader,
     b_version: usize,
     b_type: PacketType,
-) -> BitsPacket {
-    let num_packets = read_num_packets(source, i);
+) -> Result<BitsPacket, EndOfData> {
+    let num_packets = bits.read_bits(11)? as usize;
     let mut packets = Vec::with_capacity(num_packets);
 
     for _ in 0..num_packets {
-        if let Some(packet) = read_packet(source, i) {
-            packets.push(packet);
-        } else {
-            panic!("Lost our way parsing a nested packet... ");
-        }
+        packets.push(read_packet(bits)?);
     }
 
-    BitsPacket {
+    Ok(BitsPacket {
         b_version,
         b_type,
         b_literal: None,
         b_len_type: Some(LengthTypeId::NumPackets),
         b_len: Some(num_packets),
         b_packets: Some(packets),
-    }
+    })
 }
 
-fn read_packet(source: &str, i: &mut usize) -> Option<BitsPacket> {
-    if *i < source.len() {
-        let b_version = read_header(source, i);
-        let b_type = read_type(source, i);
+fn read_packet(bits: &mut BitReader) -> Result<BitsPacket, EndOfData> {
+    let b_version = bits.read_bits(3)? as usize;
+    let b_type = read_type(bits)?;
 
-        if b_type == PacketType::Literal {
-            return Some(read_packet_literal(source, i, b_version));
-        } else {
-            let b_len_type = read_len_type_id(source, i);
-            match b_len_type {
-                LengthTypeId::NumBytes => {
-                    return Some(read_num_bytes(source, i, b_version, b_type))
-                }
-                LengthTypeId::NumPackets => {
-                    return Some(read_num_subpackets(source, i, b_version, b_type))
-                }
-            }
+    if b_type == PacketType::Literal {
+        read_packet_literal(bits, b_version)
+    } else {
+        match read_len_type_id(bits)? {
+            LengthTypeId::NumBytes => read_num_bytes(bits, b_version, b_type),
+            LengthTypeId::NumPackets => read_num_subpackets(bits, b_version, b_type),
         }
     }
-    None
 }
 
 fn bits_transmission(hex_input: &str) -> BitsPacket {
-    let seq = convert_to_binary_from_hex(hex_input);
-    let mut i = 0;
-    read_packet(&seq, &mut i).unwrap()
-}
-
-fn convert_to_binary_from_hex(hex: &str) -> String {
-    hex.chars().map(to_binary).collect()
-}
-
-fn to_binary(c: char) -> &'static str {
-    match c {
-        '0' => "0000",
-        '1' => "0001",
-        '2' => "0010",
-        '3' => "0011",
-        '4' => "0100",
-        '5' => "0101",
-        '6' => "0110",
-        '7' => "0111",
-        '8' => "1000",
-        '9' => "1001",
-        'A' => "1010",
-        'B' => "1011",
-        'C' => "1100",
-        'D' => "1101",
-        'E' => "1110",
-        'F' => "1111",
-        _ => "",
+    let data = bits::hex_bytes(hex_input).unwrap();
+    match read_packet(&mut BitReader::new(&data)) {
+        Ok(packet) => packet,
+        Err(e) => panic!("Lost our way parsing a packet... {}", e),
     }
 }
 
@@ -372,4 +299,10 @@ mod tests {
         println!("{:?}", packet);
         assert_eq!(1, packet.calculate());
     }
+
+    #[test]
+    fn test_truncated() {
+        let data = bits::hex_bytes("D2FE").unwrap();
+        assert!(read_packet(&mut BitReader::new(&data)).is_err());
+    }
 }
@@ -25,6 +25,8 @@ mod day_15;
 // mod day_24;
 // mod day_25;
 
+use crate::warn;
+
 pub fn run(day: i32) {
     match day {
         1 => day_01::run(),
@@ -57,6 +59,6 @@ pub fn run(day: i32) {
         // 25 => day_25::run(),
 
         // Handle the rest of cases
-        _ => println!("Nothing to see here"),
+        _ => warn!("Nothing to see here"),
     }
 }
@@ -1,4 +1,5 @@
 use crate::puzzle_input;
+use crate::ranges::RangeSet;
 use std::ops::RangeInclusive;
 
 pub fn run() {
@@ -21,15 +22,11 @@ fn get_ranges(s: &str) -> (RangeInclusive<i32>, RangeInclusive<i32>) {
 }
 
 fn range_contains(r1: &RangeInclusive<i32>, r2: &RangeInclusive<i32>) -> bool {
-    (r1.contains(&r2.start()) && r1.contains(&r2.end()))
-        || (r2.contains(&r1.start()) && r2.contains(&r1.end()))
+    RangeSet::from(r1.clone()).contains_range(r2) || RangeSet::from(r2.clone()).contains_range(r1)
 }
 
 fn range_overlaps(r1: &RangeInclusive<i32>, r2: &RangeInclusive<i32>) -> bool {
-    r1.contains(&r2.start())
-        || r1.contains(&r2.end())
-        || r2.contains(&r1.start())
-        || r2.contains(&r1.end())
+    RangeSet::from(r1.clone()).overlaps(r2)
 }
 
 fn eval_assignments(input: &[String]) -> (i32, i32) {
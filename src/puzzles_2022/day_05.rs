@@ -1,7 +1,9 @@
+use crate::animation;
+use crate::debug;
 use crate::puzzle_input;
+use crate::render::Text;
+use crate::scan;
 use itertools::Itertools;
-use lazy_static::lazy_static;
-use regex::Regex;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2022-d05-input.txt");
@@ -16,24 +18,20 @@ pub fn run() {
 }
 
 fn get_stacks(input: &[String]) -> (Vec<Vec<char>>, Vec<(usize, usize, usize)>) {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"(\[(.)\]|   ) ?").unwrap();
-        static ref MOVE: Regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-    }
     let mut stacks: Vec<Vec<char>> = Vec::new();
     let mut instructions: Vec<(usize, usize, usize)> = Vec::new();
 
     stacks.push(Vec::with_capacity(1)); // 0
-    for line in input {
+    for (n, line) in input.iter().enumerate() {
         if line.is_empty() {
             continue;
         }
 
         if line.contains("[") {
-            let mut i = 1;
-            for caps in RE.captures_iter(line) {
-                if let Some(j) = caps.get(2) {
-                    let ch = j.as_str().chars().nth(0).unwrap();
+            // each crate is "[X]", and they are 4 columns apart
+            for (i, ch) in line.chars().skip(1).step_by(4).enumerate() {
+                let i = i + 1; // stack 0 is unused
+                if ch.is_ascii_alphabetic() {
                     if let Some(stack) = stacks.get_mut(i) {
                         stack.insert(0, ch);
                     } else {
@@ -44,20 +42,17 @@ fn get_stacks(input: &[String]) -> (Vec<Vec<char>>, Vec<(usize, usize, usize)>)
                 } else if stacks.len() <= i {
                     stacks.push(Vec::new());
                 }
-                i += 1;
             }
         } else if line.starts_with("move") {
-            let caps = MOVE.captures(line).unwrap();
-            instructions.push((
-                caps[1].parse::<usize>().unwrap(),
-                caps[2].parse::<usize>().unwrap(),
-                caps[3].parse::<usize>().unwrap(),
-            ));
+            instructions.push(
+                scan!(line, "move {} from {} to {}", usize, usize, usize)
+                    .unwrap_or_else(|e| panic!("What is this? {}", e.at_line(n + 1))),
+            );
         }
     }
 
-    println!("{:?}", stacks);
-    println!("{:?}", instructions);
+    debug!("{:?}", stacks);
+    debug!("{:?}", instructions);
 
     (stacks, instructions)
 }
@@ -67,11 +62,12 @@ fn move_one_by_one(
     instructions: &Vec<(usize, usize, usize)>,
 ) -> Vec<char> {
     for (n, from, to) in instructions {
-        println!("move {:?} from {:?} to {:?}", n, from, to);
+        debug!("move {:?} from {:?} to {:?}", n, from, to);
         for i in 0..*n {
             let x = &stacks[*from].pop().unwrap();
             let _ = &stacks[*to].push(*x);
-            println!("{:?}: {:?}", i, stacks);
+            debug!("{:?}: {:?}", i, stacks);
+            animation::frame_with(|| Text::new(&draw_stacks(stacks)));
         }
     }
     get_top_crates(stacks)
@@ -81,17 +77,35 @@ fn move_many_crates(
     stacks: &mut Vec<Vec<char>>,
     instructions: &Vec<(usize, usize, usize)>,
 ) -> Vec<char> {
-    println!("{:?}", stacks);
+    debug!("{:?}", stacks);
     for (n, from, to) in instructions {
-        println!("move {:?} from {:?} to {:?}", n, from, to);
+        debug!("move {:?} from {:?} to {:?}", n, from, to);
         let idx = &stacks[*from].len() - n;
         let mut s: Vec<char> = stacks[*from].splice(idx.., []).collect();
         let _ = &stacks[*to].append(&mut s);
-        println!("{:?}", stacks);
+        debug!("{:?}", stacks);
     }
     get_top_crates(stacks)
 }
 
+// Stacks drawn the way the puzzle input shows them
+fn draw_stacks(stacks: &[Vec<char>]) -> String {
+    let stacks = &stacks[1..];
+    let height = stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for y in (0..height).rev() {
+        let row = stacks
+            .iter()
+            .map(|s| s.get(y).map_or("   ".to_string(), |c| format!("[{}]", c)))
+            .join(" ");
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out.push_str(&(1..=stacks.len()).map(|i| format!(" {} ", i)).join(" "));
+    out.push('\n');
+    out
+}
+
 fn get_top_crates(stacks: &Vec<Vec<char>>) -> Vec<char> {
     let mut result = Vec::with_capacity(stacks.len());
     for (i, stack) in stacks.iter().enumerate() {
@@ -131,6 +145,11 @@ move 1 from 1 to 2
         let (mut stacks, instructions) = get_stacks(&input);
         let mut stacks2 = stacks.clone();
 
+        assert_eq!(
+            draw_stacks(&stacks),
+            "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3 \n"
+        );
+
         let result = move_one_by_one(&mut stacks, &instructions);
         assert_eq!(result.iter().join(""), "CMZ");
 
@@ -1,5 +1,6 @@
 use itertools::Itertools;
 
+use crate::debug;
 use crate::puzzle_input;
 pub fn run() {
     let input: String = puzzle_input::read_string("./input/2022-d06-input.txt");
@@ -12,7 +13,7 @@ fn find(input: &str, how_many: usize) -> usize {
     let mut i = how_many;
     for w in input.as_bytes().windows(how_many) {
         if w.iter().unique().count() == how_many {
-            println!("{:?}: Found marker {:?}", i, w.iter().join(""));
+            debug!("{:?}: Found marker {:?}", i, w.iter().join(""));
             return i;
         }
         i += 1;
@@ -1,5 +1,7 @@
 use crate::puzzle_input;
-use regex::Regex;
+use crate::render::{self, Text};
+use crate::scan;
+use crate::trace;
 use std::collections::HashMap;
 
 pub fn run() {
@@ -12,6 +14,7 @@ pub fn run() {
         "** Part 2 Final: {:?}",
         free_candidate(&sizes, 70000000, 30000000)
     );
+    render::emit(&Text::new(&tree.render()));
 }
 
 #[derive(Clone, Debug)]
@@ -89,22 +92,21 @@ impl DirTree {
         total
     }
 
-    #[allow(dead_code)]
-    fn dump(&self) {
-        let root = &self.elements[0];
-        self.print(root, "");
+    fn render(&self) -> String {
+        let mut out = String::new();
+        self.list(&self.elements[0], "", &mut out);
+        out
     }
 
-    #[allow(dead_code)]
-    fn print(&self, dir: &Dir, prefix: &str) {
-        println!("{}+ {}", prefix, dir.path);
+    fn list(&self, dir: &Dir, prefix: &str, out: &mut String) {
+        out.push_str(&format!("{}+ {}\n", prefix, dir.path));
         let indent = prefix.to_owned() + "  ";
         for (name, size) in dir.files.iter() {
-            println!("{}- {} ({})", indent, name, size);
+            out.push_str(&format!("{}- {} ({})\n", indent, name, size));
         }
         for (_, idx) in dir.dirs.iter() {
             let d = &self.elements[*idx];
-            self.print(d, &indent);
+            self.list(d, &indent, out);
         }
     }
 }
@@ -128,37 +130,27 @@ impl Dir {
 }
 
 fn dir_tree(input: &[String]) -> DirTree {
-    lazy_static! {
-        static ref CD: Regex = Regex::new(r"\$ cd (.*)").unwrap();
-        static ref LS: Regex = Regex::new(r"\$ ls").unwrap();
-        static ref FILE: Regex = Regex::new(r"(\d+) (.*)").unwrap();
-        static ref DIR: Regex = Regex::new(r"dir (.*)").unwrap();
-    }
-
     let mut dirtree = DirTree::new();
     let mut iter = input.iter();
 
     while let Some(line) = iter.next() {
         if line.is_empty() {
             continue;
-        } else if CD.is_match(line) {
-            let caps = CD.captures(line).unwrap();
-            match &caps[1] {
+        } else if let Ok((dir,)) = scan!(line, "$ cd {}", String) {
+            match dir.as_str() {
                 "/" => dirtree.root(),
                 ".." => dirtree.out(),
                 x => dirtree.cd(x),
             };
-            // println!("CD: {:?}", dirtree.pwd());
-        } else if DIR.is_match(line) {
-            let caps = DIR.captures(line).unwrap();
-            dirtree.add_dir(&caps[1]);
-            // println!(" +- : {:?}", line);
-        } else if FILE.is_match(line) {
-            let caps = FILE.captures(line).unwrap();
-            dirtree.add_file(&caps[2], (&caps[1]).parse::<i32>().unwrap());
-            // println!(" : {:?}", line);
+            trace!("CD: {:?}", dirtree.elements[dirtree.current].path);
+        } else if let Ok((dir,)) = scan!(line, "dir {}", String) {
+            dirtree.add_dir(&dir);
+            trace!(" +- : {:?}", line);
+        } else if let Ok((size, name)) = scan!(line, "{} {}", i32, String) {
+            dirtree.add_file(&name, size);
+            trace!(" : {:?}", line);
         } else if !line.starts_with("$") {
-            // println!("LS: {:?}", line);
+            trace!("LS: {:?}", line);
         }
     }
     dirtree
@@ -215,7 +207,7 @@ mod tests {
         );
 
         let tree = dir_tree(&input);
-        tree.dump();
+        println!("{}", tree.render());
         let sizes = tree.du();
         println!("{:?}", sizes);
 
@@ -1,7 +1,11 @@
+use crate::animation;
+use crate::point::{Direction, Point2};
 use crate::puzzle_input;
-use regex::Regex;
+use crate::render::Text;
+use crate::scan;
+use itertools::Itertools;
 use std::collections::HashSet;
-use std::ops;
+use std::convert::TryFrom;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2022-d09-input.txt");
@@ -13,133 +17,84 @@ pub fn run() {
     println!("** Part 2 Final: {:?}", move_rope(&input, &mut grid));
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Coord {
-    x: i32,
-    y: i32,
-}
-impl Coord {
-    fn new(x: i32, y: i32) -> Coord {
-        Coord { x, y }
-    }
-}
-impl ops::Add for Coord {
-    type Output = Coord;
-
-    fn add(self, rhs: Coord) -> Self::Output {
-        Coord {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-const U: Coord = Coord { x: 0, y: 1 };
-const D: Coord = Coord { x: 0, y: -1 };
-const UL: Coord = Coord { x: -1, y: 1 };
-const L: Coord = Coord { x: -1, y: 0 };
-const DL: Coord = Coord { x: -1, y: -1 };
-const UR: Coord = Coord { x: 1, y: 1 };
-const R: Coord = Coord { x: 1, y: 0 };
-const DR: Coord = Coord { x: 1, y: -1 };
-
 #[derive(Clone, Debug, PartialEq)]
 struct Grid {
     // It literally does not matter: this is an imaginary
     // grid not a real one, so let's do (x, y) using cartesian
     // semantics rather than bonkers array indices
-    data: HashSet<Coord>,
-    snake: Vec<Coord>,
+    data: HashSet<Point2>,
+    snake: Vec<Point2>,
     len: usize,
 }
 impl Grid {
     fn new(len: usize) -> Grid {
         let mut data = HashSet::new();
-        data.insert(Coord::new(0, 0));
+        data.insert(Point2::new(0, 0));
         Grid {
             data,
-            snake: vec![Coord::new(0, 0); len],
+            snake: vec![Point2::new(0, 0); len],
             len,
         }
     }
 
     fn step(&mut self, d: char, n: i32) {
-        let offset = match d {
-            'U' => &U,
-            'D' => &D,
-            'L' => &L,
-            'R' => &R,
-            _ => panic!("Unknown direction: {:?}", d),
-        };
+        let offset = Direction::try_from(d).unwrap().offset();
 
         for _step in 0..n {
-            self.snake[0] = self.snake[0] + *offset;
+            self.snake[0] += offset;
             for j in 1..self.len {
                 self.snake[j] = self.tail(j - 1, j);
             }
             self.data.insert(self.snake[self.len - 1]);
+            animation::frame_with(|| Text::new(&self.render()));
         }
     }
 
-    fn tail(&mut self, i: usize, j: usize) -> Coord {
-        if self.snake[i] == self.snake[j]
-            || ((self.snake[i].y - self.snake[j].y).abs() <= 1
-                && (self.snake[i].x - self.snake[j].x).abs() <= 1)
-        {
+    fn tail(&mut self, i: usize, j: usize) -> Point2 {
+        let delta = self.snake[i] - self.snake[j];
+        if delta.chebyshev(&Point2::ORIGIN) <= 1 {
+            // still touching
             return self.snake[j];
         }
+        // one step (possibly diagonal) toward the knot ahead
+        self.snake[j] + delta.signum()
+    }
 
-        self.snake[j]
-            + if self.snake[i].x == self.snake[j].x {
-                if self.snake[i].y > self.snake[j].y {
-                    U
-                } else {
-                    D
-                }
-            } else if self.snake[i].y == self.snake[j].y {
-                if self.snake[i].x > self.snake[j].x {
-                    R
-                } else {
-                    L
-                }
-            } else if self.snake[i].x > self.snake[j].x {
-                if self.snake[i].y > self.snake[j].y {
-                    UR
-                } else {
-                    DR
-                }
-            } else {
-                if self.snake[i].y > self.snake[j].y {
-                    UL
-                } else {
-                    DL
-                }
+    // Visited squares and the knots of the rope, north at the top
+    fn render(&self) -> String {
+        let points = || self.data.iter().chain(self.snake.iter());
+        let (x0, x1) = points().map(|p| p.x()).minmax().into_option().unwrap();
+        let (y0, y1) = points().map(|p| p.y()).minmax().into_option().unwrap();
+
+        let mut out = String::new();
+        for y in (y0..=y1).rev() {
+            for x in x0..=x1 {
+                let p = Point2::new(x, y);
+                out.push(match self.snake.iter().position(|k| *k == p) {
+                    Some(0) => 'H',
+                    Some(i) => std::char::from_digit(i as u32, 36).unwrap(),
+                    None if self.data.contains(&p) => '#',
+                    None => '.',
+                });
             }
+            out.push('\n');
+        }
+        out
     }
 
     #[allow(dead_code)]
     fn reset(&mut self) {
-        self.snake = vec![Coord::new(0, 0); self.len];
+        self.snake = vec![Point2::new(0, 0); self.len];
         self.data.clear();
-        self.data.insert(Coord::new(0, 0));
+        self.data.insert(Point2::new(0, 0));
     }
 }
 
 fn move_rope(input: &[String], grid: &mut Grid) -> usize {
-    lazy_static! {
-        static ref INSTR: Regex = Regex::new(r"(L|R|U|D) (\d+)").unwrap();
-    }
-
-    for line in input {
-        if INSTR.is_match(line) {
-            let caps = INSTR.captures(line).unwrap();
-            grid.step(
-                (&caps[1]).chars().next().unwrap(),
-                (&caps[2]).parse::<i32>().unwrap(),
-            );
-        } else {
-            panic!("What is this? {:?}", line);
-        }
+    let moves = scan::lines(input, |line| scan!(line, "{} {}", char, i32))
+        .unwrap_or_else(|e| panic!("What is this? {}", e));
+    for (d, n) in moves {
+        grid.step(d, n);
     }
 
     grid.data.len()
@@ -165,36 +120,36 @@ mod tests {
         let mut grid = Grid::new(2);
 
         grid.step('R', 4);
-        assert_eq!(grid.snake[0], Coord::new(4, 0));
-        assert_eq!(grid.snake[1], Coord::new(3, 0));
+        assert_eq!(grid.snake[0], Point2::new(4, 0));
+        assert_eq!(grid.snake[1], Point2::new(3, 0));
 
         grid.step('U', 4);
-        assert_eq!(grid.snake[0], Coord::new(4, 4));
-        assert_eq!(grid.snake[1], Coord::new(4, 3));
+        assert_eq!(grid.snake[0], Point2::new(4, 4));
+        assert_eq!(grid.snake[1], Point2::new(4, 3));
 
         grid.step('L', 3);
-        assert_eq!(grid.snake[0], Coord::new(1, 4));
-        assert_eq!(grid.snake[1], Coord::new(2, 4));
+        assert_eq!(grid.snake[0], Point2::new(1, 4));
+        assert_eq!(grid.snake[1], Point2::new(2, 4));
 
         grid.step('D', 1);
-        assert_eq!(grid.snake[0], Coord::new(1, 3));
-        assert_eq!(grid.snake[1], Coord::new(2, 4));
+        assert_eq!(grid.snake[0], Point2::new(1, 3));
+        assert_eq!(grid.snake[1], Point2::new(2, 4));
 
         grid.step('R', 4);
-        assert_eq!(grid.snake[0], Coord::new(5, 3));
-        assert_eq!(grid.snake[1], Coord::new(4, 3));
+        assert_eq!(grid.snake[0], Point2::new(5, 3));
+        assert_eq!(grid.snake[1], Point2::new(4, 3));
 
         grid.step('D', 1);
-        assert_eq!(grid.snake[0], Coord::new(5, 2));
-        assert_eq!(grid.snake[1], Coord::new(4, 3));
+        assert_eq!(grid.snake[0], Point2::new(5, 2));
+        assert_eq!(grid.snake[1], Point2::new(4, 3));
 
         grid.step('L', 5);
-        assert_eq!(grid.snake[0], Coord::new(0, 2));
-        assert_eq!(grid.snake[1], Coord::new(1, 2));
+        assert_eq!(grid.snake[0], Point2::new(0, 2));
+        assert_eq!(grid.snake[1], Point2::new(1, 2));
 
         grid.step('R', 2);
-        assert_eq!(grid.snake[0], Coord::new(2, 2));
-        assert_eq!(grid.snake[1], Coord::new(1, 2));
+        assert_eq!(grid.snake[0], Point2::new(2, 2));
+        assert_eq!(grid.snake[1], Point2::new(1, 2));
 
         assert_eq!(grid.data.len(), 13);
         grid.reset();
@@ -218,52 +173,52 @@ mod tests {
         let mut grid = Grid::new(10);
 
         grid.step('R', 4);
-        assert_eq!(grid.snake[0], Coord::new(4, 0));
-        assert_eq!(grid.snake[1], Coord::new(3, 0));
-        assert_eq!(grid.snake[3], Coord::new(1, 0));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(4, 0));
+        assert_eq!(grid.snake[1], Point2::new(3, 0));
+        assert_eq!(grid.snake[3], Point2::new(1, 0));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         grid.step('U', 4);
-        assert_eq!(grid.snake[0], Coord::new(4, 4));
-        assert_eq!(grid.snake[1], Coord::new(4, 3));
-        assert_eq!(grid.snake[3], Coord::new(3, 2));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(4, 4));
+        assert_eq!(grid.snake[1], Point2::new(4, 3));
+        assert_eq!(grid.snake[3], Point2::new(3, 2));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         grid.step('L', 3);
-        assert_eq!(grid.snake[0], Coord::new(1, 4));
-        assert_eq!(grid.snake[1], Coord::new(2, 4));
-        assert_eq!(grid.snake[3], Coord::new(3, 2));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(1, 4));
+        assert_eq!(grid.snake[1], Point2::new(2, 4));
+        assert_eq!(grid.snake[3], Point2::new(3, 2));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         grid.step('D', 1);
-        assert_eq!(grid.snake[0], Coord::new(1, 3));
-        assert_eq!(grid.snake[1], Coord::new(2, 4));
-        assert_eq!(grid.snake[3], Coord::new(3, 2));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(1, 3));
+        assert_eq!(grid.snake[1], Point2::new(2, 4));
+        assert_eq!(grid.snake[3], Point2::new(3, 2));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         grid.step('R', 4);
-        assert_eq!(grid.snake[0], Coord::new(5, 3));
-        assert_eq!(grid.snake[1], Coord::new(4, 3));
-        assert_eq!(grid.snake[3], Coord::new(3, 2));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(5, 3));
+        assert_eq!(grid.snake[1], Point2::new(4, 3));
+        assert_eq!(grid.snake[3], Point2::new(3, 2));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         grid.step('D', 1);
-        assert_eq!(grid.snake[0], Coord::new(5, 2));
-        assert_eq!(grid.snake[1], Coord::new(4, 3));
-        assert_eq!(grid.snake[3], Coord::new(3, 2));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(5, 2));
+        assert_eq!(grid.snake[1], Point2::new(4, 3));
+        assert_eq!(grid.snake[3], Point2::new(3, 2));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         grid.step('L', 5);
-        assert_eq!(grid.snake[0], Coord::new(0, 2));
-        assert_eq!(grid.snake[1], Coord::new(1, 2));
-        assert_eq!(grid.snake[3], Coord::new(3, 2));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(0, 2));
+        assert_eq!(grid.snake[1], Point2::new(1, 2));
+        assert_eq!(grid.snake[3], Point2::new(3, 2));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         grid.step('R', 2);
-        assert_eq!(grid.snake[0], Coord::new(2, 2));
-        assert_eq!(grid.snake[1], Coord::new(1, 2));
-        assert_eq!(grid.snake[3], Coord::new(3, 2));
-        assert_eq!(grid.snake[9], Coord::new(0, 0));
+        assert_eq!(grid.snake[0], Point2::new(2, 2));
+        assert_eq!(grid.snake[1], Point2::new(1, 2));
+        assert_eq!(grid.snake[3], Point2::new(3, 2));
+        assert_eq!(grid.snake[9], Point2::new(0, 0));
 
         assert_eq!(grid.data.len(), 1);
         grid.reset();
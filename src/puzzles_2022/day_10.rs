@@ -1,5 +1,8 @@
+use crate::info;
+use crate::ocr;
 use crate::puzzle_input;
-use regex::Regex;
+use crate::render::{self, Text};
+use crate::scan;
 use std::collections::VecDeque;
 
 pub fn run() {
@@ -12,7 +15,10 @@ pub fn run() {
     let result = part1.run(20, 40);
     println!("** Part 1 Final: {:?}", result.iter().sum::<i64>());
 
-    part2.scan_crt();
+    let image = part2.scan_crt();
+    info!("\n{}", image);
+    println!("** Part 2 Final: {:?}", ocr::recognize(&image));
+    render::emit(&Text::new(&image));
 }
 
 #[derive(Clone, Debug)]
@@ -58,21 +64,14 @@ impl ClockCircuit {
     }
 
     fn program(&mut self, input: &[String]) {
-        lazy_static! {
-            static ref INSTR: Regex = Regex::new(r"(\S+) ([\d-]+)").unwrap();
-        }
-        for line in input {
+        for (n, line) in input.iter().enumerate() {
             if line.eq("noop") {
                 self.cpu.noop();
-            } else if INSTR.is_match(line) {
-                let caps = INSTR.captures(line).unwrap();
-                let value = (&caps[2]).parse::<i64>().unwrap();
-                match &caps[1] {
-                    "addx" => self.cpu.addx(value),
-                    _ => panic!("What is this? {:?} {:?}", line, value),
-                }
             } else {
-                panic!("What is this? {:?}", line);
+                match scan!(line, "addx {}", i64) {
+                    Ok((value,)) => self.cpu.addx(value),
+                    Err(e) => panic!("What is this? {}", e.at_line(n + 1)),
+                }
             }
         }
     }
@@ -91,22 +90,24 @@ impl ClockCircuit {
         result
     }
 
-    fn scan_crt(&mut self) {
+    fn scan_crt(&mut self) -> String {
+        let mut screen = String::new();
         let mut cycle = 1;
         let mut sprite = self.cpu.tick();
         while sprite.1 {
             if (cycle - 2..=cycle).contains(&sprite.0) {
-                print!("#");
+                screen.push('#');
             } else {
-                print!(".");
+                screen.push('.');
             }
             if cycle % 40 == 0 {
-                println!();
+                screen.push('\n');
                 cycle = 0;
             }
             sprite = self.cpu.tick();
             cycle += 1;
         }
+        screen
     }
 
     #[allow(dead_code)]
@@ -302,6 +303,10 @@ mod tests {
         assert_eq!(result[5], 3960);
         assert_eq!(result.iter().sum::<i64>(), 13140);
 
-        crt.scan_crt();
+        let screen = crt.scan_crt();
+        let rows: Vec<&str> = screen.lines().collect();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0], "##..##..##..##..##..##..##..##..##..##..");
+        assert_eq!(rows[5], "#######.......#######.......#######.....");
     }
 }
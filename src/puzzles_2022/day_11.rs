@@ -1,5 +1,6 @@
 use itertools::Itertools;
 
+use crate::math;
 use crate::puzzle_input;
 use core::slice::Iter;
 use std::collections::VecDeque;
@@ -14,7 +15,7 @@ pub fn run() {
         monkey_business(&mut monkeys, n);
     }
 
-    let lcd: i64 = part2.iter().map(|x| x.test).product();
+    let lcd = math::lcm_all(part2.iter().map(|x| x.test));
     for _ in 0..10000 {
         monkey_business_scaled(&mut part2, n, lcd);
     }
@@ -241,7 +242,7 @@ Monkey 3:
 
         assert_eq!(level(&monkeys), 10605);
 
-        let lcd: i64 = monkeys.iter().map(|x| x.test).product();
+        let lcd = math::lcm_all(monkeys.iter().map(|x| x.test));
 
         monkey_business_scaled(&mut part2, n, lcd);
         assert_eq!(part2[0].inspected, 2);
@@ -1,23 +1,27 @@
+use crate::animation;
+use crate::debug;
 use crate::puzzle_input;
+use crate::render::{self, Render};
+use crate::sparse::SparseGrid;
 use itertools::Itertools;
 use std::cmp;
-use std::collections::HashMap;
 
 pub fn run() {
     let input: Vec<String> = puzzle_input::read_all_lines("./input/2022-d14-input.txt");
 
     let mut rocks = Rocks::new(&input, false);
     println!("** Part 1 Final: {:?}", rocks.fill_with_sand());
-    rocks.draw();
+    debug!("\n{}", render::ascii(&rocks, false));
 
     rocks = Rocks::new(&input, true);
     println!("** Part 2 Final: {:?}", rocks.fill_with_sand());
-    rocks.draw();
+    debug!("\n{}", render::ascii(&rocks, false));
+    render::emit(&rocks);
 }
 
 const OFFSETS: [(i32, i32); 3] = [(0, 1), (-1, 1), (1, 1)];
 
-fn add_rocks(data: &mut HashMap<(i32, i32), char>, p1: (i32, i32), p2: (i32, i32)) {
+fn add_rocks(data: &mut SparseGrid<char>, p1: (i32, i32), p2: (i32, i32)) {
     if p1.0 == p2.0 {
         for i in cmp::min(p1.1, p2.1)..=cmp::max(p1.1, p2.1) {
             data.insert((p1.0, i), '#');
@@ -32,14 +36,15 @@ fn add_rocks(data: &mut HashMap<(i32, i32), char>, p1: (i32, i32), p2: (i32, i32
 
 #[derive(Clone, Debug, PartialEq)]
 struct Rocks {
-    data: HashMap<(i32, i32), char>,
+    data: SparseGrid<char>,
     y_max: i32,
     floor: bool,
 }
 impl Rocks {
     fn new(input: &[String], floor: bool) -> Rocks {
-        let mut data = HashMap::new();
-        let mut y_max = 0 as i32;
+        let mut data = SparseGrid::new();
+        // sand pours in from here
+        data.include((500, 0));
 
         for line in input.iter() {
             // 503,4 -> 502,4 -> 502,9 -> 494,9
@@ -52,18 +57,16 @@ impl Rocks {
                         p.next().unwrap().parse::<i32>().unwrap(),
                     )
                 })
-                .inspect(|p| {
-                    if p.1 > y_max {
-                        y_max = p.1;
-                    }
-                })
                 .tuple_windows::<(_, _)>()
             {
                 add_rocks(&mut data, t.0, t.1);
             }
         }
+        let mut y_max = data.bounds().map_or(0, |(_, max)| max.1);
         if floor {
+            // sand can settle on the row just above the floor
             y_max += 1;
+            data.include((500, y_max));
         }
         Rocks { data, y_max, floor }
     }
@@ -71,7 +74,7 @@ impl Rocks {
     fn fall(&mut self, start: (i32, i32)) -> Option<(i32, i32)> {
         for o in OFFSETS {
             let p = (start.0 + o.0, start.1 + o.1);
-            if !self.data.contains_key(&p) {
+            if !self.data.contains(p) {
                 if self.floor && p.1 > self.y_max {
                     break;
                 }
@@ -92,6 +95,7 @@ impl Rocks {
                 }
             }
             self.data.insert(grain, 'o');
+            animation::frame(self);
             n += 1;
             if grain == (500, 0) {
                 break;
@@ -99,36 +103,21 @@ impl Rocks {
         }
         n
     }
+}
 
-    #[allow(dead_code)]
-    fn draw(&self) {
-        let mut x_min = 500;
-        let mut x_max = 500;
-        self.data.keys().for_each(|p| {
-            if p.0 < x_min {
-                x_min = p.0;
-            } else if p.0 > x_max {
-                x_max = p.0;
-            }
-        });
+// The floor, when there is one, is drawn as an extra row of rock
+impl Render for Rocks {
+    fn size(&self) -> (usize, usize) {
+        let (width, height) = self.data.size();
+        (width, height + self.floor as usize)
+    }
 
-        for y in 0..=self.y_max {
-            for x in x_min..=x_max {
-                if let Some(p) = self.data.get(&(x, y)) {
-                    print!("{}", p);
-                } else {
-                    print!(".");
-                }
-            }
-            println!();
-        }
-        if self.floor {
-            for _ in x_min..=x_max {
-                print!("#");
-            }
-            println!();
+    fn glyph(&self, x: usize, y: usize) -> char {
+        if y == self.data.height() {
+            '#'
+        } else {
+            self.data.glyph(x, y)
         }
-        println!();
     }
 }
 
@@ -144,13 +133,14 @@ mod tests {
         );
         let mut rocks = Rocks::new(&input, false);
         assert_eq!(rocks.fill_with_sand(), 24);
-        rocks.draw();
+        println!("{}", render::ascii(&rocks, false));
 
         println!("====");
 
         rocks = Rocks::new(&input, true);
-        rocks.draw();
+        assert_eq!(rocks.size(), (10, 12));
         assert_eq!(rocks.fill_with_sand(), 93);
-        rocks.draw();
+        println!("{}", render::ascii(&rocks, false));
+        assert!(render::ascii(&rocks, false).ends_with(&format!("\n{}\n", "#".repeat(21))));
     }
 }
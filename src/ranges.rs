@@ -0,0 +1,263 @@
+use num_traits::PrimInt;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::RangeInclusive;
+
+// A set of integers stored as sorted, disjoint, non-adjacent inclusive ranges.
+// Touching ranges are merged: 1..=3 and 4..=6 become 1..=6.
+
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet { ranges: Vec::new() }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(s, e)| *s..=*e)
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.1)
+    }
+
+    // Number of values in the set
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |acc, (s, e)| acc + (*e - *s) + T::one())
+    }
+
+    // Index of the first range that ends at or after v
+    fn search(&self, v: T) -> usize {
+        self.ranges.partition_point(|r| r.1 < v)
+    }
+
+    pub fn contains(&self, v: T) -> bool {
+        match self.ranges.get(self.search(v)) {
+            Some(r) => r.0 <= v,
+            None => false,
+        }
+    }
+
+    // True if every value in the range is in the set
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        match self.ranges.get(self.search(*range.start())) {
+            Some(r) => r.0 <= *range.start() && *range.end() <= r.1,
+            None => false,
+        }
+    }
+
+    // True if any value in the range is in the set
+    pub fn overlaps(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return false;
+        }
+        match self.ranges.get(self.search(*range.start())) {
+            Some(r) => r.0 <= *range.end(),
+            None => false,
+        }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = range.into_inner();
+        // first range that touches or follows the new one
+        let lo = self
+            .ranges
+            .partition_point(|r| r.1 < start && r.1 + T::one() < start);
+        let mut hi = lo;
+        while hi < self.ranges.len()
+            && (self.ranges[hi].0 <= end || end + T::one() == self.ranges[hi].0)
+        {
+            start = start.min(self.ranges[hi].0);
+            end = end.max(self.ranges[hi].1);
+            hi += 1;
+        }
+        self.ranges.splice(lo..hi, Some((start, end)));
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = self.clone();
+        for r in other.ranges() {
+            result.insert(r);
+        }
+        result
+    }
+
+    #[allow(dead_code)]
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let start = a.0.max(b.0);
+            let end = a.1.min(b.1);
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for (s, e) in self.ranges.iter() {
+            let mut start = *s;
+            // skip holes that end before this range
+            while j < other.ranges.len() && other.ranges[j].1 < start {
+                j += 1;
+            }
+            let mut k = j;
+            let mut open = true;
+            while k < other.ranges.len() && other.ranges[k].0 <= *e {
+                let (hs, he) = other.ranges[k];
+                if hs > start {
+                    ranges.push((start, hs - T::one()));
+                }
+                if he >= *e {
+                    open = false;
+                    break;
+                }
+                start = he + T::one();
+                k += 1;
+            }
+            if open {
+                ranges.push((start, *e));
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    // The values within bounds that are not in the set
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> RangeSet<T> {
+        RangeSet::from(bounds).difference(self)
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for RangeSet<T> {
+    fn from(range: RangeInclusive<T>) -> RangeSet<T> {
+        let mut result = RangeSet::new();
+        result.insert(range);
+        result
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> RangeSet<T> {
+        let mut result = RangeSet::new();
+        for r in iter {
+            result.insert(r);
+        }
+        result
+    }
+}
+
+impl<T: PrimInt + fmt::Display> fmt::Debug for RangeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (s, e)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}..={}", s, e)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i32, i32)]) -> RangeSet<i32> {
+        ranges.iter().map(|(s, e)| *s..=*e).collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let s = set(&[(5, 7), (1, 3), (10, 12)]);
+        assert_eq!(format!("{:?}", s), "{1..=3, 5..=7, 10..=12}");
+        assert_eq!(set(&[(1, 3), (4, 6)]), set(&[(1, 6)]));
+        assert_eq!(set(&[(1, 3), (5, 7), (2, 11)]), set(&[(1, 11)]));
+        assert_eq!(set(&[(5, 7), (1, 3), (4, 4)]), set(&[(1, 7)]));
+        assert_eq!(set(&[(3, 1)]), RangeSet::new());
+        assert_eq!(s.total_len(), 9);
+        assert_eq!(s.min(), Some(1));
+        assert_eq!(s.max(), Some(12));
+
+        // ranges far apart, and at the ends of the type
+        let far = set(&[
+            (-2_000_000_000, -2_000_000_000),
+            (2_000_000_000, 2_000_000_000),
+        ]);
+        assert_eq!(far.ranges().count(), 2);
+        let ends = set(&[
+            (i32::MAX, i32::MAX),
+            (i32::MIN, i32::MIN),
+            (i32::MIN + 1, 0),
+        ]);
+        assert_eq!(ends, set(&[(i32::MIN, 0), (i32::MAX, i32::MAX)]));
+        assert_eq!(
+            set(&[(i32::MAX, i32::MAX), (0, i32::MAX - 1)]),
+            set(&[(0, i32::MAX)])
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let s = set(&[(1, 3), (5, 7)]);
+        assert!(s.contains(1));
+        assert!(!s.contains(4));
+        assert!(!s.contains(8));
+        assert!(s.contains_range(&(5..=7)));
+        assert!(!s.contains_range(&(3..=5)));
+        assert!(s.overlaps(&(3..=5)));
+        assert!(!s.overlaps(&(8..=9)));
+        assert!(!s.overlaps(&(4..=4)));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(1, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(1, 4), (26, 27)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (31, 40)]));
+        assert_eq!(a.difference(&a), RangeSet::new());
+        assert_eq!(a.difference(&RangeSet::new()), a);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let s = set(&[(-2, 2), (4, 8), (12, 20)]);
+        assert_eq!(s.gaps(0..=20), set(&[(3, 3), (9, 11)]));
+        assert_eq!(s.gaps(0..=8), set(&[(3, 3)]));
+        assert_eq!(s.gaps(30..=31), set(&[(30, 31)]));
+    }
+}
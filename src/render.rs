@@ -0,0 +1,308 @@
+use crate::grid::Grid;
+use crate::info;
+use crate::sparse::SparseGrid;
+use std::fmt;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Drawing puzzle state: anything that can be seen as a rectangle of character
+// cells can be written out as plain or colored text, a PPM or PGM image, or an SVG.
+// With --render <file>, days hand their final state to emit(), and the
+// file extension picks the format.
+
+pub type Color = (u8, u8, u8);
+
+pub trait Render {
+    // (width, height) in cells
+    fn size(&self) -> (usize, usize);
+
+    fn glyph(&self, x: usize, y: usize) -> char;
+
+    fn color(&self, x: usize, y: usize) -> Color {
+        palette(self.glyph(x, y))
+    }
+}
+
+const BLACK: Color = (0, 0, 0);
+const WHITE: Color = (255, 255, 255);
+const COLORS: [Color; 8] = [
+    (230, 25, 75),
+    (60, 180, 75),
+    (255, 225, 25),
+    (0, 130, 200),
+    (245, 130, 48),
+    (145, 30, 180),
+    (70, 240, 240),
+    (240, 50, 230),
+];
+
+// Blank cells are black, '#' is white, digits are shades of grey,
+// and every other character gets a color of its own
+pub fn palette(glyph: char) -> Color {
+    match glyph {
+        ' ' | '.' => BLACK,
+        '#' => WHITE,
+        '0'..='9' => {
+            let v = 55 + (glyph as u8 - b'0') * 20;
+            (v, v, v)
+        }
+        c => COLORS[c as usize % COLORS.len()],
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ascii,
+    Ansi, // ascii with 24-bit terminal colors
+    Ppm,
+    Pgm, // greyscale
+    Svg,
+}
+
+impl Format {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Format {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("ppm") => Format::Ppm,
+            Some("pgm") => Format::Pgm,
+            Some("svg") => Format::Svg,
+            Some("ans") => Format::Ansi,
+            _ => Format::Ascii,
+        }
+    }
+}
+
+pub fn ascii(r: &dyn Render, ansi: bool) -> String {
+    let (width, height) = r.size();
+    let mut out = String::with_capacity((width + 1) * height);
+    for y in 0..height {
+        let mut current = None;
+        for x in 0..width {
+            if ansi {
+                let color = r.color(x, y);
+                if current != Some(color) {
+                    let _ = write!(out, "\x1b[38;2;{};{};{}m", color.0, color.1, color.2);
+                    current = Some(color);
+                }
+            }
+            out.push(r.glyph(x, y));
+        }
+        if ansi {
+            out.push_str("\x1b[0m");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// Binary PPM (P6), each cell drawn as a scale x scale square
+pub fn ppm(r: &dyn Render, scale: usize) -> Vec<u8> {
+    let (width, height) = r.size();
+    let mut out = format!("P6\n{} {}\n255\n", width * scale, height * scale).into_bytes();
+    for y in 0..height {
+        let row: Vec<u8> = (0..width)
+            .flat_map(|x| {
+                let (red, green, blue) = r.color(x, y);
+                [red, green, blue].repeat(scale)
+            })
+            .collect();
+        for _ in 0..scale {
+            out.extend(&row);
+        }
+    }
+    out
+}
+
+// Binary PGM (P5): the brightness of each color
+pub fn pgm(r: &dyn Render, scale: usize) -> Vec<u8> {
+    let (width, height) = r.size();
+    let mut out = format!("P5\n{} {}\n255\n", width * scale, height * scale).into_bytes();
+    for y in 0..height {
+        let row: Vec<u8> = (0..width)
+            .flat_map(|x| {
+                let (red, green, blue) = r.color(x, y);
+                let grey = (red as u32 * 299 + green as u32 * 587 + blue as u32 * 114) / 1000;
+                [grey as u8].repeat(scale)
+            })
+            .collect();
+        for _ in 0..scale {
+            out.extend(&row);
+        }
+    }
+    out
+}
+
+// One square per cell that isn't black, on a black background
+pub fn svg(r: &dyn Render, scale: usize) -> String {
+    let (width, height) = r.size();
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        width * scale,
+        height * scale
+    );
+    let _ = writeln!(out, "<rect width=\"100%\" height=\"100%\" fill=\"black\"/>");
+    for y in 0..height {
+        for x in 0..width {
+            let (red, green, blue) = r.color(x, y);
+            if (red, green, blue) != BLACK {
+                let _ = writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+                    x * scale,
+                    y * scale,
+                    scale,
+                    scale,
+                    red,
+                    green,
+                    blue
+                );
+            }
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+pub fn write<P: AsRef<Path>>(r: &dyn Render, path: P) -> io::Result<()> {
+    match Format::from_path(&path) {
+        Format::Ascii => fs::write(path, ascii(r, false)),
+        Format::Ansi => fs::write(path, ascii(r, true)),
+        Format::Ppm => fs::write(path, ppm(r, 4)),
+        Format::Pgm => fs::write(path, pgm(r, 4)),
+        Format::Svg => fs::write(path, svg(r, 10)),
+    }
+}
+
+static TARGET: OnceLock<String> = OnceLock::new();
+
+// Set once from the command line
+pub fn set_target(path: &str) {
+    TARGET
+        .set(path.to_string())
+        .unwrap_or_else(|_| panic!("Render target is already set"));
+}
+
+// Write r to the --render file, if there is one (the last call wins)
+pub fn emit(r: &dyn Render) {
+    if let Some(path) = TARGET.get() {
+        write(r, path).unwrap_or_else(|e| panic!("Unable to write {}: {}", path, e));
+        info!("Rendered to {}", path);
+    }
+}
+
+impl<R: Render + ?Sized> Render for &R {
+    fn size(&self) -> (usize, usize) {
+        (**self).size()
+    }
+
+    fn glyph(&self, x: usize, y: usize) -> char {
+        (**self).glyph(x, y)
+    }
+
+    fn color(&self, x: usize, y: usize) -> Color {
+        (**self).color(x, y)
+    }
+}
+
+// Lines of text, padded with spaces to the longest line
+pub struct Text(Vec<Vec<char>>);
+
+impl Text {
+    pub fn new(text: &str) -> Text {
+        Text(text.lines().map(|line| line.chars().collect()).collect())
+    }
+}
+
+impl Render for Text {
+    fn size(&self) -> (usize, usize) {
+        let width = self.0.iter().map(|line| line.len()).max().unwrap_or(0);
+        (width, self.0.len())
+    }
+
+    fn glyph(&self, x: usize, y: usize) -> char {
+        self.0[y].get(x).copied().unwrap_or(' ')
+    }
+}
+
+impl<T: fmt::Display> Render for Grid<T> {
+    fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+
+    fn glyph(&self, x: usize, y: usize) -> char {
+        self[(y, x)].to_string().chars().next().unwrap_or(' ')
+    }
+}
+
+impl Render for SparseGrid<char> {
+    fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+
+    fn glyph(&self, x: usize, y: usize) -> char {
+        self.bounds()
+            .and_then(|(min, _)| self.get((min.0 + x as i32, min.1 + y as i32)))
+            .copied()
+            .unwrap_or('.')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle_input;
+
+    #[test]
+    fn test_backends() {
+        let grid = Grid::parse(&puzzle_input::split_string("#.\n.a"), |c| c);
+        assert_eq!(ascii(&grid, false), "#.\n.a\n");
+        assert_eq!(
+            ascii(&grid, true),
+            "\x1b[38;2;255;255;255m#\x1b[38;2;0;0;0m.\x1b[0m\n\x1b[38;2;0;0;0m.\x1b[38;2;60;180;75ma\x1b[0m\n"
+        );
+
+        let image = ppm(&grid, 2);
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 4 * 4 * 3);
+        // second row of pixels is still the first row of cells
+        let row = &image[header.len() + 12..header.len() + 24];
+        assert_eq!(row, &[255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0][..]);
+
+        let drawing = svg(&grid, 10);
+        assert!(drawing.contains("width=\"20\" height=\"20\""));
+        assert!(
+            drawing.contains("<rect x=\"0\" y=\"0\" width=\"10\" height=\"10\" fill=\"#ffffff\"/>")
+        );
+        assert!(drawing
+            .contains("<rect x=\"10\" y=\"10\" width=\"10\" height=\"10\" fill=\"#3cb44b\"/>"));
+        assert_eq!(drawing.matches("<rect").count(), 3);
+
+        let grey = pgm(&grid, 1);
+        let header = b"P5\n2 2\n255\n";
+        assert_eq!(&grey[..header.len()], header);
+        assert_eq!(&grey[header.len()..], &[255, 0, 0, 132][..]);
+    }
+
+    #[test]
+    fn test_sources() {
+        let text = Text::new("ab\nc");
+        assert_eq!(text.size(), (2, 2));
+        assert_eq!(ascii(&text, false), "ab\nc \n");
+
+        let mut sparse = SparseGrid::new();
+        sparse.insert((-2, 5), '#');
+        sparse.insert((0, 6), 'o');
+        assert_eq!(ascii(&sparse, false), "#..\n..o\n");
+
+        assert_eq!(Format::from_path("out/day.svg"), Format::Svg);
+        assert_eq!(Format::from_path("day.ppm"), Format::Ppm);
+        assert_eq!(Format::from_path("day.pgm"), Format::Pgm);
+        assert_eq!(Format::from_path("day.ans"), Format::Ansi);
+        assert_eq!(Format::from_path("day.txt"), Format::Ascii);
+    }
+}
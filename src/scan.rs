@@ -0,0 +1,195 @@
+use std::any::type_name;
+use std::fmt;
+use std::str::FromStr;
+
+// Reading values out of lines of text with simple patterns, instead of regular expressions.
+// Each {} in the pattern captures text up to the literal that follows it
+// (or the end of the line), and is parsed into the matching type:
+//
+//   let (n, from, to) = scan!(line, "move {} from {} to {}", usize, usize, usize)?;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScanError {
+    pub line: Option<usize>,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ScanError {
+    fn new(column: usize, message: String) -> ScanError {
+        ScanError {
+            line: None,
+            column,
+            message,
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> ScanError {
+        self.line = Some(line);
+        self
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line)?;
+        }
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capture<'a> {
+    pub text: &'a str,
+    pub column: usize,
+}
+
+// Columns count characters from 1
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+// The text matched by each {} in the pattern
+pub fn split<'a>(pattern: &str, line: &'a str) -> Result<Vec<Capture<'a>>, ScanError> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+    let mut captures = Vec::with_capacity(literals.len() - 1);
+
+    if !line.starts_with(literals[0]) {
+        return Err(ScanError::new(1, format!("expected {:?}", literals[0])));
+    }
+    let mut offset = literals[0].len();
+    for (i, literal) in literals.iter().enumerate().skip(1) {
+        let rest = &line[offset..];
+        let end = if literal.is_empty() {
+            assert!(
+                i == literals.len() - 1,
+                "Pattern {:?} has {{}} with nothing between them",
+                pattern
+            );
+            rest.len()
+        } else {
+            match rest.find(literal) {
+                Some(end) => end,
+                None => {
+                    return Err(ScanError::new(
+                        column(line, offset),
+                        format!("expected {:?} after this", literal),
+                    ))
+                }
+            }
+        };
+        captures.push(Capture {
+            text: &rest[..end],
+            column: column(line, offset),
+        });
+        offset += end + literal.len();
+    }
+
+    if offset < line.len() {
+        return Err(ScanError::new(
+            column(line, offset),
+            format!("unexpected {:?}", &line[offset..]),
+        ));
+    }
+    Ok(captures)
+}
+
+pub fn parse<T: FromStr>(capture: &Capture) -> Result<T, ScanError> {
+    capture.text.parse::<T>().map_err(|_| {
+        ScanError::new(
+            capture.column,
+            format!("can't read {:?} as {}", capture.text, type_name::<T>()),
+        )
+    })
+}
+
+// Scan every non-empty line, adding the line number (from 1) to any error
+pub fn lines<'a, T, F>(input: &'a [String], mut f: F) -> Result<Vec<T>, ScanError>
+where
+    F: FnMut(&'a str) -> Result<T, ScanError>,
+{
+    input
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| f(line).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+// scan!(line, pattern, types...) -> Result<(types...), ScanError>
+#[macro_export]
+macro_rules! scan {
+    ($line:expr, $pattern:expr, $($t:ty),+ $(,)?) => {
+        $crate::scan::split($pattern, $line).and_then(|captures| {
+            let types = [$(stringify!($t)),+];
+            assert_eq!(
+                captures.len(),
+                types.len(),
+                "Pattern {:?} doesn't match types {:?}",
+                $pattern,
+                types
+            );
+            let mut captures = captures.iter();
+            Ok(($($crate::scan::parse::<$t>(captures.next().unwrap())?,)+))
+        })
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(
+            scan!(
+                "move 1 from 2 to 13",
+                "move {} from {} to {}",
+                usize,
+                usize,
+                usize
+            ),
+            Ok((1, 2, 13))
+        );
+        assert_eq!(
+            scan!("Sensor at x=2, y=-18", "Sensor at x={}, y={}", i64, i64),
+            Ok((2, -18))
+        );
+        assert_eq!(
+            scan!(
+                "departure time: 1-3 or 5-7",
+                "{}: {}-{} or {}-{}",
+                String,
+                u32,
+                u32,
+                u32,
+                u32
+            ),
+            Ok(("departure time".to_string(), 1, 3, 5, 7))
+        );
+        assert_eq!(scan!("R 4", "{} {}", char, i32), Ok(('R', 4)));
+        assert_eq!(scan!("170cm", "{}cm", u32), Ok((170,)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = scan!("move 1 to 3", "move {} from {} to {}", u8, u8, u8).unwrap_err();
+        assert_eq!(err.column, 6);
+        assert_eq!(err.to_string(), "column 6: expected \" from \" after this");
+
+        let err = scan!("move x from 2", "move {} from {}", u8, u8).unwrap_err();
+        assert_eq!(err.to_string(), "column 6: can't read \"x\" as u8");
+
+        assert_eq!(scan!("noop", "addx {}", i32).unwrap_err().column, 1);
+        assert_eq!(scan!("170cm!", "{}cm", u32).unwrap_err().column, 6);
+        assert!(scan!("150in", "{}cm", u32).is_err());
+
+        let input: Vec<String> = vec!["1,2".to_string(), "".to_string(), "3;4".to_string()];
+        let err = lines(&input, |line| scan!(line, "{},{}", i32, i32)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: expected \",\" after this"
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// Path finding over implicit graphs: nodes are any hashable value, and
+// edges come from a closure that returns the neighbors of a node.
+// Each search returns the path (including the start and the goal),
+// and, for weighted searches, the total cost of that path.
+
+// Walk back from the goal to whichever start it came from
+fn reconstruct<N>(parents: &HashMap<N, Option<N>>, goal: N) -> Vec<N>
+where
+    N: Eq + Hash + Clone,
+{
+    let mut path = vec![goal];
+    while let Some(Some(prev)) = parents.get(path.last().unwrap()) {
+        path.push(prev.clone());
+    }
+    path.reverse();
+    path
+}
+
+// Breadth-first search for the fewest steps from any of the starting nodes to the goal
+pub fn bfs_multi<N, S, FN, IN, FG>(starts: S, mut neighbors: FN, mut goal: FG) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    S: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut parents: HashMap<N, Option<N>> = HashMap::new();
+    let mut queue: VecDeque<N> = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = parents.entry(start.clone()) {
+            e.insert(None);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        if goal(&current) {
+            return Some(reconstruct(&parents, current));
+        }
+        for next in neighbors(&current) {
+            if let Entry::Vacant(e) = parents.entry(next.clone()) {
+                e.insert(Some(current.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+#[allow(dead_code)]
+pub fn bfs<N, FN, IN, FG>(start: N, neighbors: FN, goal: FG) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    bfs_multi(Some(start), neighbors, goal)
+}
+
+// Dijkstra with a bucket queue (Dial's algorithm): nodes are kept in one
+// bucket per total cost, which works well when edge weights are small integers.
+pub fn dijkstra_multi<N, S, FN, IN, FG>(
+    starts: S,
+    mut neighbors: FN,
+    mut goal: FG,
+) -> Option<(Vec<N>, usize)>
+where
+    N: Eq + Hash + Clone,
+    S: IntoIterator<Item = N>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut parents: HashMap<N, Option<N>> = HashMap::new();
+    let mut costs: HashMap<N, usize> = HashMap::new();
+    let mut buckets: Vec<Vec<N>> = vec![Vec::new()];
+    for start in starts {
+        costs.insert(start.clone(), 0);
+        parents.insert(start.clone(), None);
+        buckets[0].push(start);
+    }
+
+    let mut cost = 0;
+    while cost < buckets.len() {
+        while let Some(current) = buckets[cost].pop() {
+            if costs[&current] < cost {
+                continue; // stale: already reached more cheaply
+            }
+            if goal(&current) {
+                return Some((reconstruct(&parents, current), cost));
+            }
+            for (next, weight) in neighbors(&current) {
+                let total = cost + weight;
+                if !matches!(costs.get(&next), Some(c) if *c <= total) {
+                    costs.insert(next.clone(), total);
+                    parents.insert(next.clone(), Some(current.clone()));
+                    if total >= buckets.len() {
+                        buckets.resize_with(total + 1, Vec::new);
+                    }
+                    buckets[total].push(next);
+                }
+            }
+        }
+        cost += 1;
+    }
+    None
+}
+
+pub fn dijkstra<N, FN, IN, FG>(start: N, neighbors: FN, goal: FG) -> Option<(Vec<N>, usize)>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FG: FnMut(&N) -> bool,
+{
+    dijkstra_multi(Some(start), neighbors, goal)
+}
+
+// A* search: the heuristic must never overestimate the remaining cost
+pub fn astar<N, FN, IN, FH, FG>(
+    start: N,
+    mut neighbors: FN,
+    mut heuristic: FH,
+    mut goal: FG,
+) -> Option<(Vec<N>, usize)>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, usize)>,
+    FH: FnMut(&N) -> usize,
+    FG: FnMut(&N) -> bool,
+{
+    // Nodes are numbered as they are found, so the heap doesn't need N: Ord
+    let mut ids: HashMap<N, usize> = HashMap::new();
+    let mut nodes: Vec<N> = vec![start.clone()];
+    let mut costs: Vec<usize> = vec![0];
+    let mut parents: Vec<Option<usize>> = vec![None];
+    let mut heap = BinaryHeap::new();
+    ids.insert(start.clone(), 0);
+    heap.push(Reverse((heuristic(&start), 0, 0)));
+
+    while let Some(Reverse((_, cost, id))) = heap.pop() {
+        if cost > costs[id] {
+            continue;
+        }
+        if goal(&nodes[id]) {
+            let mut path = vec![nodes[id].clone()];
+            let mut at = id;
+            while let Some(prev) = parents[at] {
+                path.push(nodes[prev].clone());
+                at = prev;
+            }
+            path.reverse();
+            return Some((path, cost));
+        }
+        for (next, weight) in neighbors(&nodes[id].clone()) {
+            let total = cost + weight;
+            let next_id = match ids.get(&next) {
+                Some(n) if costs[*n] <= total => continue,
+                Some(n) => *n,
+                None => {
+                    nodes.push(next.clone());
+                    costs.push(total);
+                    parents.push(None);
+                    ids.insert(next.clone(), nodes.len() - 1);
+                    nodes.len() - 1
+                }
+            };
+            costs[next_id] = total;
+            parents[next_id] = Some(id);
+            heap.push(Reverse((total + heuristic(&next), total, next_id)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A small maze: '#' is a wall, digits are the cost of entering a cell
+    const MAZE: [&str; 5] = ["S1111", "9###1", "11#91", "1#111", "111#E"];
+
+    fn at(p: (i32, i32)) -> Option<char> {
+        MAZE.get(p.0 as usize)
+            .and_then(|row| row.chars().nth(p.1 as usize))
+            .filter(|c| *c != '#')
+    }
+
+    fn steps(p: &(i32, i32)) -> Vec<(i32, i32)> {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .iter()
+            .map(|o| (p.0 + o.0, p.1 + o.1))
+            .filter(|n| n.0 >= 0 && n.1 >= 0 && at(*n).is_some())
+            .collect()
+    }
+
+    fn weighted(p: &(i32, i32)) -> Vec<((i32, i32), usize)> {
+        steps(p)
+            .into_iter()
+            .map(|n| (n, at(n).unwrap().to_digit(10).unwrap_or(1) as usize))
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs((0, 0), steps, |p| *p == (4, 4)).unwrap();
+        assert_eq!(path.len() - 1, 8);
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(*path.last().unwrap(), (4, 4));
+        assert!(path.windows(2).all(|w| steps(&w[0]).contains(&w[1])));
+
+        assert_eq!(bfs((0, 0), steps, |p| *p == (1, 1)), None);
+
+        // closest of several starts
+        let path = bfs_multi(vec![(0, 0), (3, 4)], steps, |p| *p == (4, 4)).unwrap();
+        assert_eq!(path, vec![(3, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // around the top and down the right side, avoiding the 9s
+        let (path, cost) = dijkstra((0, 0), weighted, |p| *p == (4, 4)).unwrap();
+        assert_eq!(cost, 8);
+        assert_eq!(path.len(), 9);
+
+        let (path, cost) = astar(
+            (0, 0),
+            weighted,
+            |p| ((4 - p.0) + (4 - p.1)) as usize,
+            |p| *p == (4, 4),
+        )
+        .unwrap();
+        assert_eq!(cost, 8);
+        assert_eq!(path.len(), 9);
+
+        assert_eq!(dijkstra((0, 0), weighted, |p| *p == (1, 1)), None);
+        assert_eq!(
+            dijkstra_multi(vec![(0, 0), (2, 0)], weighted, |p| *p == (3, 0)),
+            Some((vec![(2, 0), (3, 0)], 1))
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::iter::FromIterator;
+
+// A grid on an unbounded plane, storing only the cells that have been set.
+// Points are (x, y) in screen order: x grows to the right and y grows down,
+// as they appear in puzzle input. The bounding box of everything that has
+// been set is tracked as cells are added (it never shrinks).
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    data: HashMap<(i32, i32), T>,
+    bounds: Option<((i32, i32), (i32, i32))>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid {
+            data: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // Top left and bottom right corners (inclusive)
+    pub fn bounds(&self) -> Option<((i32, i32), (i32, i32))> {
+        self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds
+            .map_or(0, |(min, max)| (max.0 - min.0 + 1) as usize)
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds
+            .map_or(0, |(min, max)| (max.1 - min.1 + 1) as usize)
+    }
+
+    // Grow the bounds to cover pt, without setting a value
+    pub fn include(&mut self, pt: (i32, i32)) {
+        self.bounds = Some(match self.bounds {
+            None => (pt, pt),
+            Some((min, max)) => (
+                (min.0.min(pt.0), min.1.min(pt.1)),
+                (max.0.max(pt.0), max.1.max(pt.1)),
+            ),
+        });
+    }
+
+    pub fn contains(&self, pt: (i32, i32)) -> bool {
+        self.data.contains_key(&pt)
+    }
+
+    pub fn get(&self, pt: (i32, i32)) -> Option<&T> {
+        self.data.get(&pt)
+    }
+
+    // Returns the value that was there before
+    pub fn insert(&mut self, pt: (i32, i32), value: T) -> Option<T> {
+        self.include(pt);
+        self.data.insert(pt, value)
+    }
+
+    pub fn get_or_insert_with<F>(&mut self, pt: (i32, i32), f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.include(pt);
+        self.data.entry(pt).or_insert_with(f)
+    }
+
+    // Every point inside the bounds, row by row
+    #[allow(dead_code)]
+    pub fn points(&self) -> impl Iterator<Item = (i32, i32)> {
+        let ((x0, y0), (x1, y1)) = self.bounds.unwrap_or(((0, 0), (-1, -1)));
+        (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+    }
+
+    // Cells that have been set, row by row
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = ((i32, i32), &T)> {
+        let mut cells: Vec<_> = self.data.iter().map(|(pt, v)| (*pt, v)).collect();
+        cells.sort_unstable_by_key(|((x, y), _)| (*y, *x));
+        cells.into_iter()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.data.values()
+    }
+
+    // One line per row within the bounds, with empty cells drawn as blank
+    #[allow(dead_code)]
+    pub fn render<F>(&self, blank: char, f: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut out = String::with_capacity((self.width() + 1) * self.height());
+        if let Some(((x0, y0), (x1, y1))) = self.bounds {
+            for y in y0..=y1 {
+                out.extend((x0..=x1).map(|x| self.get((x, y)).map_or(blank, &f)));
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+impl<T> FromIterator<((i32, i32), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((i32, i32), T)>>(iter: I) -> SparseGrid<T> {
+        let mut grid = SparseGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<((i32, i32), T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = ((i32, i32), T)>>(&mut self, iter: I) {
+        for (pt, value) in iter {
+            self.insert(pt, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let mut grid: SparseGrid<char> = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.render('.', |c| *c), "");
+
+        grid.insert((2, 1), 'a');
+        assert_eq!(grid.bounds(), Some(((2, 1), (2, 1))));
+        grid.insert((-1, 3), 'b');
+        grid.include((0, 0));
+        assert_eq!(grid.bounds(), Some(((-1, 0), (2, 3))));
+        assert_eq!((grid.width(), grid.height(), grid.len()), (4, 4, 2));
+        assert_eq!(grid.points().count(), 16);
+
+        *grid.get_or_insert_with((3, 3), || 'c') = 'd';
+        assert_eq!(grid.insert((2, 1), 'e'), Some('a'));
+        assert_eq!(grid.get((3, 3)), Some(&'d'));
+        assert!(!grid.contains((0, 0)));
+        assert_eq!(grid.render('.', |c| *c), ".....\n...e.\n.....\nb...d\n");
+    }
+
+    #[test]
+    fn test_iter() {
+        let grid: SparseGrid<i32> = vec![((5, 2), 1), ((-3, 2), 2), ((9, -4), 3), ((0, 0), 4)]
+            .into_iter()
+            .collect();
+        let cells: Vec<_> = grid.iter().map(|(pt, v)| (pt, *v)).collect();
+        assert_eq!(
+            cells,
+            vec![((9, -4), 3), ((0, 0), 4), ((-3, 2), 2), ((5, 2), 1)]
+        );
+        assert_eq!(grid.values().sum::<i32>(), 10);
+    }
+}